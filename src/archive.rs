@@ -0,0 +1,218 @@
+//! ffmpeg 압축 파일에서 필요한 실행 파일만 꺼내기
+//!
+//! tar.xz는 xz2로 푸는 즉시 `tar` 크레이트로 항목을 읽어 지정한 파일만 쓴다 (전체 빌드 폴더를 풀지 않음).
+//! 꺼낸 파일은 `<이름>.part`에 쓴 뒤 이름을 바꾸므로 중간에 실패해도 반쯤 쓴 실행 파일이 남지 않고,
+//! 그 전에 꺼낸 파일도 지운다.
+
+use crate::error::Error;
+use std::fs;
+use std::io::{self, BufReader, Read, Write};
+use std::path::Path;
+use zip::ZipArchive;
+
+/// tar.xz에서 파일 이름이 `names` 중 하나인 일반 파일만 `dest_dir`에 꺼내고 꺼낸 이름 반환
+pub fn extract_tar_xz(archive: &Path, names: &[String], dest_dir: &Path) -> Result<Vec<String>, Error> {
+    let file = fs::File::open(archive)?;
+    extract_tar(xz2::read::XzDecoder::new(BufReader::new(file)), names, dest_dir)
+}
+
+/// 압축하지 않은 tar 스트림에서 꺼내기 (`extract_tar_xz` 참고)
+pub fn extract_tar<R: Read>(reader: R, names: &[String], dest_dir: &Path) -> Result<Vec<String>, Error> {
+    let mut extracted = Vec::new();
+    let result = tar_entries(reader, names, dest_dir, &mut extracted);
+    finish(result, extracted, dest_dir)
+}
+
+fn tar_entries<R: Read>(reader: R, names: &[String], dest_dir: &Path, extracted: &mut Vec<String>) -> Result<(), Error> {
+    let mut archive = tar::Archive::new(reader);
+    for entry in archive.entries().map_err(read_error)? {
+        let mut entry = entry.map_err(read_error)?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        // GNU 긴 이름/PAX 경로는 `tar`가 풀어 준다
+        let path = entry.path().map_err(read_error)?;
+        let file_name = path.file_name()
+            .and_then(|n| n.to_str())
+            .filter(|n| names.iter().any(|wanted| wanted == n))
+            .map(str::to_string);
+        if let Some(file_name) = file_name {
+            let size = entry.size();
+            write_executable(&mut entry, size, &dest_dir.join(&file_name))?;
+            extracted.push(file_name);
+        }
+    }
+    Ok(())
+}
+
+/// zip에서 파일 이름이 `names` 중 하나인 파일만 `dest_dir`에 꺼내고 꺼낸 이름 반환
+pub fn extract_zip(archive: &Path, names: &[String], dest_dir: &Path) -> Result<Vec<String>, Error> {
+    let mut extracted = Vec::new();
+    let result = zip_entries(archive, names, dest_dir, &mut extracted);
+    finish(result, extracted, dest_dir)
+}
+
+fn zip_entries(archive: &Path, names: &[String], dest_dir: &Path, extracted: &mut Vec<String>) -> Result<(), Error> {
+    let file = fs::File::open(archive)?;
+    let mut archive = ZipArchive::new(BufReader::new(file)).map_err(|e| Error::Archive(e.to_string()))?;
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i).map_err(|e| Error::Archive(e.to_string()))?;
+        if entry.is_dir() {
+            continue;
+        }
+        let name = entry.name().to_string();
+        let file_name = Path::new(&name).file_name().and_then(|n| n.to_str());
+        if let Some(file_name) = file_name.filter(|n| names.iter().any(|wanted| wanted == n)) {
+            let size = entry.size();
+            write_executable(&mut entry, size, &dest_dir.join(file_name))?;
+            extracted.push(file_name.to_string());
+        }
+    }
+    Ok(())
+}
+
+/// 중간에 실패하면 이미 꺼낸 파일도 지운다 (짝이 맞지 않는 ffmpeg/ffprobe가 남지 않도록)
+fn finish(result: Result<(), Error>, extracted: Vec<String>, dest_dir: &Path) -> Result<Vec<String>, Error> {
+    if result.is_err() {
+        for name in &extracted {
+            let _ = fs::remove_file(dest_dir.join(name));
+        }
+    }
+    result.map(|()| extracted)
+}
+
+/// `reader`에서 `size` 바이트를 실행 파일로 쓰기 (`.part`에 쓴 뒤 이름 변경)
+fn write_executable(reader: &mut impl Read, size: u64, dest: &Path) -> Result<(), Error> {
+    let mut part_name = dest.as_os_str().to_owned();
+    part_name.push(".part");
+    let part = Path::new(&part_name);
+
+    let result = copy_exact(reader, size, part).and_then(|()| {
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(part, fs::Permissions::from_mode(0o755))?;
+        }
+        fs::rename(part, dest)?;
+        Ok(())
+    });
+    if result.is_err() {
+        let _ = fs::remove_file(part);
+    }
+    result
+}
+
+/// 읽기 오류(손상된 압축)와 쓰기 오류(디스크 부족 등)를 구분해 복사
+fn copy_exact(reader: &mut impl Read, size: u64, dest: &Path) -> Result<(), Error> {
+    let mut out = io::BufWriter::new(fs::File::create(dest)?);
+    let mut buf = [0u8; 64 * 1024];
+    let mut written = 0u64;
+    loop {
+        let n = reader.read(&mut buf).map_err(read_error)?;
+        if n == 0 {
+            break;
+        }
+        out.write_all(&buf[..n])?;
+        written += n as u64;
+    }
+    if written != size {
+        return Err(Error::Archive(format!("truncated entry: {} of {} bytes", written, size)));
+    }
+    out.flush()?;
+    Ok(())
+}
+
+fn read_error(e: io::Error) -> Error {
+    if e.kind() == io::ErrorKind::UnexpectedEof {
+        Error::Archive("unexpected end of archive".to_string())
+    } else {
+        Error::Archive(e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn sample_tar() -> Vec<u8> {
+        let mut builder = tar::Builder::new(Vec::new());
+        let mut append = |path: &str, data: &[u8], entry_type: tar::EntryType| {
+            let mut header = tar::Header::new_gnu();
+            header.set_entry_type(entry_type);
+            header.set_mode(0o755);
+            header.set_size(data.len() as u64);
+            builder.append_data(&mut header, path, data).unwrap();
+        };
+        append("ffmpeg-master-latest-linux64-gpl/", b"", tar::EntryType::Directory);
+        append("ffmpeg-master-latest-linux64-gpl/doc/ffmpeg.html", b"<html>", tar::EntryType::Regular);
+        append("ffmpeg-master-latest-linux64-gpl/bin/ffmpeg", b"ffmpeg binary", tar::EntryType::Regular);
+        // 100자를 넘는 경로는 GNU 긴 이름 항목으로 저장됨
+        append(&format!("{}/bin/ffprobe", "x".repeat(120)), b"ffprobe binary", tar::EntryType::Regular);
+        append("ffmpeg-master-latest-linux64-gpl/bin/ffplay", b"ffplay binary", tar::EntryType::Regular);
+        builder.into_inner().unwrap()
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("rust-yt-archive-{}-{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn names() -> Vec<String> {
+        vec!["ffmpeg".to_string(), "ffprobe".to_string()]
+    }
+
+    #[test]
+    fn extracts_only_requested_binaries() {
+        let dir = temp_dir("tar");
+        let extracted = extract_tar(sample_tar().as_slice(), &names(), &dir).unwrap();
+
+        assert_eq!(extracted, ["ffmpeg", "ffprobe"]);
+        assert_eq!(fs::read(dir.join("ffmpeg")).unwrap(), b"ffmpeg binary");
+        assert_eq!(fs::read(dir.join("ffprobe")).unwrap(), b"ffprobe binary");
+        let mut left: Vec<_> = fs::read_dir(&dir).unwrap().map(|e| e.unwrap().file_name()).collect();
+        left.sort();
+        assert_eq!(left, ["ffmpeg", "ffprobe"]);
+    }
+
+    #[test]
+    fn extracts_from_xz_stream() {
+        let dir = temp_dir("xz");
+        let archive = dir.join("ffmpeg.tar.xz");
+        let mut encoder = xz2::write::XzEncoder::new(fs::File::create(&archive).unwrap(), 6);
+        encoder.write_all(&sample_tar()).unwrap();
+        encoder.finish().unwrap();
+
+        let extracted = extract_tar_xz(&archive, &names(), &dir).unwrap();
+        assert_eq!(extracted, ["ffmpeg", "ffprobe"]);
+        assert_eq!(fs::read(dir.join("ffmpeg")).unwrap(), b"ffmpeg binary");
+    }
+
+    #[test]
+    fn damaged_archives_are_reported_without_leftovers() {
+        let dir = temp_dir("damaged");
+        let tar = sample_tar();
+
+        // ffmpeg 데이터 중간에서 끊김 (디렉터리, html 헤더+데이터, ffmpeg 헤더 다음 블록)
+        let cut = 512 * 4 + 5;
+        let err = extract_tar(&tar[..cut], &names(), &dir).unwrap_err();
+        assert!(matches!(err, Error::Archive(_)), "{:?}", err);
+        assert!(fs::read_dir(&dir).unwrap().next().is_none());
+
+        // ffmpeg는 다 꺼낸 뒤 ffprobe 데이터에서 끊김 (긴 이름 헤더+이름, ffprobe 헤더 다음 블록)
+        let cut = 512 * 8 + 5;
+        let err = extract_tar(&tar[..cut], &names(), &dir).unwrap_err();
+        assert!(matches!(err, Error::Archive(_)), "{:?}", err);
+        assert!(fs::read_dir(&dir).unwrap().next().is_none());
+
+        let mut corrupt = tar.clone();
+        corrupt[0] ^= 1;
+        let err = extract_tar(corrupt.as_slice(), &names(), &dir).unwrap_err();
+        assert!(matches!(err, Error::Archive(_)), "{:?}", err);
+
+        let err = extract_tar_xz(&dir.join("missing.tar.xz"), &names(), &dir).unwrap_err();
+        assert!(matches!(err, Error::Io(_)), "{:?}", err);
+    }
+}
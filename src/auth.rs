@@ -0,0 +1,78 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// `--cookies-from-browser`로 쿠키를 읽을 수 있는 브라우저
+pub const BROWSERS: [&str; 9] = ["brave", "chrome", "chromium", "edge", "firefox", "opera", "safari", "vivaldi", "whale"];
+
+/// 로그인이 필요한 영상(연령 제한, 멤버십, 비공개 재생목록)용 쿠키 설정
+///
+/// 쿠키 파일이 있으면 파일을, 없으면 브라우저 쿠키를 쓴다.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AuthOptions {
+    #[serde(default)]
+    pub cookies_file: Option<PathBuf>,       // Netscape 형식 cookies.txt
+    #[serde(default)]
+    pub cookies_browser: Option<String>,     // BROWSERS 중 하나
+    #[serde(default)]
+    pub browser_profile: Option<String>,     // 비우면 기본 프로필
+}
+
+impl AuthOptions {
+    pub fn is_configured(&self) -> bool {
+        self.cookies_file.is_some() || self.cookies_browser.is_some()
+    }
+
+    /// 모든 yt-dlp 실행에 붙일 인자
+    pub fn args(&self) -> Vec<String> {
+        if let Some(file) = &self.cookies_file {
+            return vec!["--cookies".to_string(), file.to_string_lossy().to_string()];
+        }
+        match &self.cookies_browser {
+            Some(browser) => {
+                let source = match self.browser_profile.as_deref().map(str::trim).filter(|p| !p.is_empty()) {
+                    Some(profile) => format!("{}:{}", browser, profile),
+                    None => browser.clone(),
+                };
+                vec!["--cookies-from-browser".to_string(), source]
+            }
+            None => Vec::new(),
+        }
+    }
+
+    /// CLI의 "browser[:profile]" 값 파싱
+    pub fn parse_browser(spec: &str) -> Option<(String, Option<String>)> {
+        let (browser, profile) = match spec.split_once(':') {
+            Some((browser, profile)) => (browser, Some(profile.trim().to_string()).filter(|p| !p.is_empty())),
+            None => (spec, None),
+        };
+        let browser = browser.trim().to_lowercase();
+        BROWSERS.contains(&browser.as_str()).then_some((browser, profile))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cookie_file_takes_priority_over_browser() {
+        assert!(AuthOptions::default().args().is_empty());
+
+        let mut auth = AuthOptions {
+            cookies_file: None,
+            cookies_browser: Some("firefox".to_string()),
+            browser_profile: Some("default-release".to_string()),
+        };
+        assert_eq!(auth.args(), ["--cookies-from-browser", "firefox:default-release"]);
+
+        auth.cookies_file = Some(PathBuf::from("/tmp/cookies.txt"));
+        assert_eq!(auth.args(), ["--cookies", "/tmp/cookies.txt"]);
+    }
+
+    #[test]
+    fn parses_browser_with_profile() {
+        assert_eq!(AuthOptions::parse_browser("Chrome"), Some(("chrome".to_string(), None)));
+        assert_eq!(AuthOptions::parse_browser("firefox:work"), Some(("firefox".to_string(), Some("work".to_string()))));
+        assert_eq!(AuthOptions::parse_browser("netscape"), None);
+    }
+}
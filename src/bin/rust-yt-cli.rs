@@ -0,0 +1,687 @@
+use clap::Parser;
+use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+use rust_yt::auth::AuthOptions;
+use rust_yt::config::AppConfig;
+use rust_yt::downloader::{DownloadConfig, SponsorCategory, SubtitleFormat, SubtitleOptions, TimeRange, VideoCodec, MAX_SLEEP_SECS};
+use rust_yt::initializer::{init_dependencies, InitStatus};
+use rust_yt::playlist::{fetch_formats, fetch_playlist_info, VideoEntry};
+use rust_yt::template::{expand_template, playlist_template, TemplateVars};
+use rust_yt::queue::{run_queue, ItemState, QueueEvent, QueueItem};
+use rust_yt::Error;
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::sync::mpsc::channel;
+use std::thread;
+
+rust_i18n::i18n!("locales");
+
+/// GUI 없이 yt-dlp 다운로드를 실행하는 커맨드라인 도구
+#[derive(Debug, Parser)]
+#[command(name = "rust-yt-cli", version, about = "Headless YouTube downloader")]
+struct Cli {
+    /// 다운로드할 영상 또는 플레이리스트 URL
+    #[arg(required = true)]
+    urls: Vec<String>,
+
+    /// 출력 형식 (기본값: 저장된 설정)
+    #[arg(short, long, value_parser = ["mp3", "wav", "m4a", "flac", "mp4", "webm"])]
+    format: Option<String>,
+
+    /// MP3/M4A 오디오 품질 (비트레이트 예: 320K, VBR: 0~9)
+    #[arg(short = 'q', long)]
+    audio_quality: Option<String>,
+
+    /// WAV/FLAC 샘플레이트 (Hz, 예: 48000)
+    #[arg(long)]
+    sample_rate: Option<u32>,
+
+    /// WAV/FLAC 비트 깊이
+    #[arg(long, value_parser = ["16", "24"])]
+    bit_depth: Option<String>,
+
+    /// MP4/WEBM 최대 해상도 (세로 픽셀, 예: 720)
+    #[arg(long)]
+    max_height: Option<u32>,
+
+    /// MP4/WEBM 최대 프레임레이트
+    #[arg(long)]
+    max_fps: Option<u32>,
+
+    /// MP4/WEBM 선호 비디오 코덱
+    #[arg(long, value_parser = ["any", "h264", "vp9", "av1"])]
+    video_codec: Option<String>,
+
+    /// 자막 받기 (언어 코드, 예: en,ko / 모든 언어: all)
+    #[arg(long, value_name = "LANGS")]
+    subs: Option<String>,
+
+    /// 자동 생성 자막도 받기
+    #[arg(long)]
+    auto_subs: bool,
+
+    /// 자막 파일 형식
+    #[arg(long, value_parser = ["srt", "vtt"])]
+    sub_format: Option<String>,
+
+    /// 자막을 MP4/WEBM 파일 안에 넣기
+    #[arg(long)]
+    embed_subs: bool,
+
+    /// 챕터마다 파일 하나씩 나누기
+    #[arg(long)]
+    split_chapters: bool,
+
+    /// 나눌 때 남길 챕터 번호 (단일 영상, 예: 1,3-5)
+    #[arg(long, requires = "split_chapters")]
+    chapters: Option<String>,
+
+    /// 이 시각부터 받기 (단일 영상만, 예: 1:02:03, 90)
+    #[arg(long)]
+    start: Option<String>,
+
+    /// 이 시각까지 받기
+    #[arg(long)]
+    end: Option<String>,
+
+    /// 구간 경계를 키프레임에 맞춰 정확히 자르기 (다시 인코딩하므로 느림)
+    #[arg(long)]
+    precise_cuts: bool,
+
+    /// SponsorBlock으로 잘라낼 구간 분류 (예: sponsor,selfpromo, 기본값: 저장된 설정)
+    #[arg(long, value_name = "CATEGORIES")]
+    sponsorblock_remove: Option<String>,
+
+    /// SponsorBlock 구간을 챕터로 표시할 분류 (예: intro,outro)
+    #[arg(long, value_name = "CATEGORIES")]
+    sponsorblock_mark: Option<String>,
+
+    /// 로그인이 필요한 영상용 Netscape 형식 cookies.txt (기본값: 저장된 설정)
+    #[arg(long, value_name = "FILE")]
+    cookies: Option<PathBuf>,
+
+    /// 브라우저에서 쿠키 읽기 (예: firefox, chrome:Profile 1)
+    #[arg(long, value_name = "BROWSER[:PROFILE]", conflicts_with = "cookies")]
+    cookies_from_browser: Option<String>,
+
+    /// HTTP/SOCKS 프록시 (예: socks5://127.0.0.1:1080, ""이면 직접 연결, 기본값: 저장된 설정)
+    #[arg(long, value_name = "URL")]
+    proxy: Option<String>,
+
+    /// 다운로드 속도 제한 (예: 500K, 2M, 기본값: 저장된 설정)
+    #[arg(long, value_name = "RATE")]
+    limit_rate: Option<String>,
+
+    /// 다운로드 사이 무작위 대기 초 (예: 10 또는 5-30, 기본값: 저장된 설정)
+    #[arg(long, value_name = "MIN[-MAX]")]
+    sleep: Option<String>,
+
+    /// 직접 고른 yt-dlp 형식 ID (예: 137+140, 화질/코덱 옵션보다 우선, 단일 영상만)
+    #[arg(long)]
+    format_id: Option<String>,
+
+    /// 저장 폴더 (기본값: 저장된 설정 또는 현재 폴더)
+    #[arg(short, long)]
+    output_dir: Option<PathBuf>,
+
+    /// 파일명 템플릿 (예: "{uploader}/{playlist}/{index:03} - {title}.{ext}")
+    #[arg(short = 't', long)]
+    output_template: Option<String>,
+
+    /// 플레이리스트는 제목 폴더에 트랙 번호를 붙여 저장 (기본값: 저장된 설정)
+    #[arg(long, overrides_with = "no_playlist_folder")]
+    playlist_folder: bool,
+
+    /// 저장된 설정과 관계없이 플레이리스트도 폴더 없이 저장
+    #[arg(long, overrides_with = "playlist_folder")]
+    no_playlist_folder: bool,
+
+    /// 플레이리스트에서 받을 항목 번호 (1부터 시작, 예: 1,3,5-8)
+    #[arg(short, long)]
+    items: Option<String>,
+
+    /// 동시 다운로드 수 (기본값: 저장된 설정)
+    #[arg(short, long)]
+    jobs: Option<usize>,
+
+    /// 같은 형식으로 이미 받은 영상 건너뛰기 (다운로드 기록 기준)
+    #[arg(long)]
+    skip_downloaded: bool,
+
+    /// 항목 목록만 출력하고 종료
+    #[arg(long)]
+    list: bool,
+
+    /// 영상별로 받을 수 있는 형식 목록만 출력하고 종료
+    #[arg(long)]
+    list_formats: bool,
+
+    /// yt-dlp/ffmpeg 설치 및 업데이트 확인 건너뛰기
+    #[arg(long)]
+    skip_init: bool,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let saved_config = AppConfig::load();
+
+    let locale = if saved_config.language == "auto" {
+        sys_locale::get_locale().unwrap_or_else(|| "en".to_string())
+    } else {
+        saved_config.language.clone()
+    };
+    rust_i18n::set_locale(&locale);
+
+    let selection = match cli.items.as_deref().map(parse_item_spec).transpose() {
+        Ok(selection) => selection,
+        Err(e) => {
+            eprintln!("{}", rust_i18n::t!("cli.invalid_items", error = e));
+            return ExitCode::FAILURE;
+        }
+    };
+    let chapter_selection = match cli.chapters.as_deref().map(parse_item_spec).transpose() {
+        Ok(selection) => selection,
+        Err(e) => {
+            eprintln!("{}", rust_i18n::t!("cli.invalid_items", error = e));
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut sponsorblock = saved_config.sponsorblock.clone();
+    for (spec, categories) in [(&cli.sponsorblock_remove, &mut sponsorblock.remove), (&cli.sponsorblock_mark, &mut sponsorblock.mark)] {
+        if let Some(spec) = spec {
+            match parse_sponsor_categories(spec) {
+                Ok(parsed) => *categories = parsed,
+                Err(e) => {
+                    eprintln!("{}", rust_i18n::t!("cli.invalid_sponsorblock", error = e));
+                    return ExitCode::FAILURE;
+                }
+            }
+        }
+    }
+
+    let mut auth = saved_config.auth.clone();
+    if let Some(file) = cli.cookies.clone() {
+        auth = AuthOptions { cookies_file: Some(file), ..AuthOptions::default() };
+    }
+    if let Some(spec) = &cli.cookies_from_browser {
+        match AuthOptions::parse_browser(spec) {
+            Some((browser, profile)) => {
+                auth = AuthOptions { cookies_file: None, cookies_browser: Some(browser), browser_profile: profile };
+            }
+            None => {
+                eprintln!("{}", rust_i18n::t!("cli.invalid_browser", browser = spec, browsers = rust_yt::auth::BROWSERS.join(", ")));
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    let proxy = match cli.proxy.as_deref() {
+        Some(proxy) => rust_yt::network::normalize_proxy(proxy),
+        None => saved_config.proxy.clone(),
+    };
+    if let Some(proxy) = &proxy
+        && let Err(e) = rust_yt::network::validate_proxy(proxy)
+    {
+        eprintln!("{}", e);
+        return ExitCode::FAILURE;
+    }
+
+    let rate_limit = match cli.limit_rate.as_deref() {
+        Some(rate) => match rust_yt::downloader::parse_rate_limit(rate) {
+            Some(kib) => Some(kib),
+            None => {
+                eprintln!("{}", rust_i18n::t!("cli.invalid_rate", rate = rate));
+                return ExitCode::FAILURE;
+            }
+        },
+        None => saved_config.rate_limit,
+    };
+    let sleep_interval = match cli.sleep.as_deref() {
+        Some(spec) => match parse_sleep_interval(spec) {
+            Ok(interval) => interval,
+            Err(_) => {
+                eprintln!("{}", rust_i18n::t!("cli.invalid_sleep", sleep = spec));
+                return ExitCode::FAILURE;
+            }
+        },
+        None => saved_config.sleep_interval,
+    };
+
+    rust_yt::binaries::set_configured_paths(saved_config.ytdlp_path.clone(), saved_config.ffmpeg_path.clone());
+    if !cli.skip_init && !run_init(proxy.clone(), !saved_config.use_system_tools) {
+        return ExitCode::FAILURE;
+    }
+
+    let format = AppConfig::string_to_format(cli.format.as_deref().unwrap_or(&saved_config.format));
+    let audio_quality = cli.audio_quality.unwrap_or(saved_config.audio_quality);
+    let sample_rate = cli.sample_rate.or(saved_config.sample_rate);
+    let bit_depth = cli.bit_depth.and_then(|d| d.parse().ok()).or(saved_config.bit_depth);
+    let max_height = cli.max_height.or(saved_config.max_height);
+    let max_fps = cli.max_fps.or(saved_config.max_fps);
+    let video_codec = match cli.video_codec.as_deref() {
+        Some("h264") => VideoCodec::H264,
+        Some("vp9") => VideoCodec::Vp9,
+        Some("av1") => VideoCodec::Av1,
+        Some(_) => VideoCodec::Any,
+        // 저장된 코덱이 이번 형식과 맞지 않으면 자동으로
+        None => Some(saved_config.video_codec).filter(|c| c.supports(&format)).unwrap_or_default(),
+    };
+    if !format.is_audio() && !video_codec.supports(&format) {
+        let codecs = VideoCodec::for_format(&format).iter().map(|c| c.label()).collect::<Vec<_>>().join(", ");
+        eprintln!("{}", rust_i18n::t!(
+            "cli.invalid_codec",
+            codec = video_codec.label(),
+            format = AppConfig::format_to_string(&format).to_uppercase(),
+            codecs = codecs
+        ));
+        return ExitCode::FAILURE;
+    }
+    let output_dir = cli.output_dir
+        .or(saved_config.download_dir)
+        .unwrap_or_else(|| PathBuf::from("."));
+    let output_template = cli.output_template.unwrap_or(saved_config.output_template);
+    let mut subtitles = saved_config.subtitles.clone();
+    if let Some(langs) = &cli.subs {
+        subtitles.enabled = true;
+        subtitles.languages = if langs == "all" { Vec::new() } else { SubtitleOptions::parse_languages(langs) };
+    }
+    subtitles.include_auto |= cli.auto_subs;
+    if cli.auto_subs && subtitles.enabled && subtitles.languages.is_empty() {
+        eprintln!("{}", rust_i18n::t!("cli.auto_subs_need_lang"));
+        return ExitCode::FAILURE;
+    }
+    subtitles.embed |= cli.embed_subs;
+    match cli.sub_format.as_deref() {
+        Some("vtt") => subtitles.format = SubtitleFormat::Vtt,
+        Some(_) => subtitles.format = SubtitleFormat::Srt,
+        None => {}
+    }
+    let playlist_folder = match (cli.playlist_folder, cli.no_playlist_folder) {
+        (true, _) => true,
+        (_, true) => false,
+        _ => saved_config.playlist_subfolder,
+    };
+
+    let jobs = cli.jobs.unwrap_or(saved_config.max_concurrent_downloads);
+
+    let mut analysis_failed = 0;
+    let mut items = Vec::new();
+
+    let list_only = cli.list || cli.list_formats;
+
+    for url in &cli.urls {
+        if cli.list_formats {
+            match fetch_formats(url, &auth, proxy.as_deref()) {
+                Ok(formats) => {
+                    println!("{}", url);
+                    for f in formats {
+                        println!(
+                            "{:>8}  {:<5} {:<12} {:>5} {:<28} {:>11} {:>10}  {}",
+                            f.format_id,
+                            f.ext,
+                            f.resolution_string(),
+                            f.fps.map(|fps| format!("{:.0}", fps)).unwrap_or_default(),
+                            f.codec_string(),
+                            f.bitrate_string(),
+                            f.size_string(),
+                            f.language.or(f.format_note).unwrap_or_default(),
+                        );
+                    }
+                }
+                Err(e) => {
+                    print_analysis_error(&e);
+                    analysis_failed += 1;
+                }
+            }
+            continue;
+        }
+
+        eprintln!("{}", rust_i18n::t!("cli.analyzing", url = url));
+        let info = match fetch_playlist_info(url, &auth, proxy.as_deref()) {
+            Ok(info) => info,
+            Err(e) => {
+                print_analysis_error(&e);
+                analysis_failed += 1;
+                continue;
+            }
+        };
+
+        if cli.list {
+            println!("{}", info.title);
+            for (idx, entry) in info.entries.iter().enumerate() {
+                let number = entry.playlist_index.unwrap_or(idx + 1);
+                let mark = if entry.downloaded_formats.contains(&format) { " ✔" } else { "" };
+                println!("{:>4}. {} [{}]{}", number, entry.title, entry.format_duration(), mark);
+            }
+            // 단일 영상은 챕터와 받을 수 있는 자막 언어도 표시
+            if !info.is_playlist && let Some(entry) = info.entries.first() {
+                for (idx, chapter) in entry.chapters.iter().enumerate() {
+                    println!("      #{:<3} {:>8}  {} ({})", idx + 1, chapter.format_start(), chapter.title, chapter.format_length());
+                }
+                for auto in [false, true] {
+                    let languages = entry.subtitles.iter()
+                        .filter(|s| s.auto == auto)
+                        .map(|s| s.language.as_str())
+                        .collect::<Vec<_>>();
+                    if !languages.is_empty() {
+                        let languages = languages.join(", ");
+                        let line = if auto {
+                            rust_i18n::t!("cli.auto_subtitles", languages = languages)
+                        } else {
+                            rust_i18n::t!("cli.subtitles", languages = languages)
+                        };
+                        println!("      {}", line);
+                    }
+                }
+            }
+            continue;
+        }
+
+        // 항목 번호는 `--list`와 같은 플레이리스트 번호
+        // 구간은 영상마다 길이가 달라 단일 영상에만 적용 (GUI와 같음)
+        if info.is_playlist && (cli.start.is_some() || cli.end.is_some()) {
+            eprintln!("{}", rust_i18n::t!("cli.clip_playlist", title = info.title));
+            analysis_failed += 1;
+            continue;
+        }
+        // 형식 ID도 영상마다 달라 단일 영상에만 적용
+        if info.is_playlist && cli.format_id.is_some() {
+            eprintln!("{}", rust_i18n::t!("cli.format_id_playlist", title = info.title));
+            analysis_failed += 1;
+            continue;
+        }
+
+        let selection = selection.as_ref().map(|s| select_items(s, info.last_index()));
+        let selected: Vec<&VideoEntry> = info.entries.iter()
+            .enumerate()
+            .filter(|(idx, entry)| !info.is_playlist || selection.as_ref().is_none_or(|s| s.contains(&entry.playlist_index.unwrap_or(idx + 1))))
+            .map(|(_, entry)| entry)
+            .filter(|entry| !cli.skip_downloaded || !entry.downloaded_formats.contains(&format))
+            .collect();
+
+        if selected.is_empty() {
+            eprintln!("{}", rust_i18n::t!("main.no_selection"));
+            continue;
+        }
+
+        let template = if info.is_playlist && playlist_folder {
+            playlist_template(&output_template, info.last_index())
+        } else {
+            output_template.clone()
+        };
+
+        for entry in selected {
+            // 고르지 않은 챕터는 잘라냄 (챕터 목록은 단일 영상에만 있음)
+            let chapters = chapter_selection.as_ref().map(|s| select_items(s, entry.chapters.len()));
+            let skipped_chapters = entry.chapters.iter()
+                .enumerate()
+                .filter(|(i, _)| chapters.as_ref().is_some_and(|s| !s.contains(&(i + 1))))
+                .map(|(_, c)| (c.start_time, c.end_time))
+                .collect();
+
+            // 구간이 영상 길이를 벗어나는지 확인
+            let time_range = match TimeRange::parse(cli.start.as_deref().unwrap_or(""), cli.end.as_deref().unwrap_or(""), entry.duration) {
+                Ok(range) => range,
+                Err(e) => {
+                    eprintln!("{} ({})", e, entry.title);
+                    return ExitCode::FAILURE;
+                }
+            };
+
+            let playlist = info.is_playlist.then_some(&info);
+            let output_name = match expand_template(&template, &TemplateVars::new(entry, playlist, &format), "%(ext)s") {
+                Ok(name) => name,
+                Err(e) => {
+                    eprintln!("{}", e);
+                    return ExitCode::FAILURE;
+                }
+            };
+
+            items.push(QueueItem {
+                id: entry.id.clone(),
+                title: entry.title.clone(),
+                config: DownloadConfig {
+                    url: entry.url.clone(),
+                    format: format.clone(),
+                    audio_quality: audio_quality.clone(),
+                    sample_rate,
+                    bit_depth,
+                    max_height,
+                    max_fps,
+                    video_codec,
+                    format_id: cli.format_id.clone(),
+                    output_dir: output_dir.clone(),
+                    output_name: Some(output_name),
+                    subtitles: subtitles.clone(),
+                    split_chapters: cli.split_chapters,
+                    skipped_chapters,
+                    time_range,
+                    precise_cuts: cli.precise_cuts,
+                    sponsorblock: sponsorblock.clone(),
+                    auth: auth.clone(),
+                    proxy: proxy.clone(),
+                    rate_limit,
+                    sleep_interval,
+                },
+            });
+        }
+    }
+
+    if list_only {
+        return if analysis_failed > 0 { ExitCode::FAILURE } else { ExitCode::SUCCESS };
+    }
+
+    let (completed, failed) = if items.is_empty() {
+        (0, 0)
+    } else {
+        run_downloads(items, jobs)
+    };
+    let failed = failed + analysis_failed;
+
+    eprintln!("{}", rust_i18n::t!("cli.summary", completed = completed, failed = failed));
+
+    if failed > 0 {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+/// 분석 실패 출력 (로그인이 필요하면 쿠키 옵션 안내)
+fn print_analysis_error(e: &Error) {
+    eprintln!("{}", rust_i18n::t!("cli.analysis_failed", error = e));
+    if matches!(e, Error::AuthRequired(_)) {
+        eprintln!("{}", rust_i18n::t!("cli.auth_hint"));
+    }
+}
+
+/// 의존성 초기화 상태를 터미널에 출력 (실패 시 false)
+fn run_init(proxy: Option<String>, auto_download: bool) -> bool {
+    let (tx, rx) = channel();
+    thread::spawn(move || init_dependencies(tx, proxy, auto_download));
+
+    let mut bar: Option<ProgressBar> = None;
+    for status in rx {
+        match status {
+            InitStatus::Starting(msg) | InitStatus::Extracting(msg) => {
+                if let Some(bar) = bar.take() {
+                    bar.finish_and_clear();
+                }
+                eprintln!("{}", msg);
+            }
+            InitStatus::Downloading(percent, file) => {
+                let bar = bar.get_or_insert_with(|| {
+                    ProgressBar::new(100).with_style(progress_style())
+                });
+                bar.set_prefix(file);
+                bar.set_position(percent as u64);
+            }
+            InitStatus::Verified(file, hash) => {
+                if let Some(bar) = bar.take() {
+                    bar.finish_and_clear();
+                }
+                eprintln!("{}", InitStatus::verification_message(&file, hash.as_deref()));
+            }
+            InitStatus::Missing(tools) => {
+                if let Some(bar) = bar.take() {
+                    bar.finish_and_clear();
+                }
+                for tool in tools {
+                    eprintln!("{}", rust_yt::binaries::missing_message(tool));
+                }
+                eprintln!("{}", rust_i18n::t!("cli.missing_tools_hint"));
+                return false;
+            }
+            InitStatus::Completed => {
+                if let Some(bar) = bar.take() {
+                    bar.finish_and_clear();
+                }
+                return true;
+            }
+            InitStatus::Failed(e) => {
+                if let Some(bar) = bar.take() {
+                    bar.finish_and_clear();
+                }
+                eprintln!("{}", e);
+                return false;
+            }
+        }
+    }
+    false
+}
+
+/// 큐를 실행하며 항목별 진행률 표시 (성공 수, 실패 수)
+fn run_downloads(items: Vec<QueueItem>, jobs: usize) -> (usize, usize) {
+    let titles: Vec<String> = items.iter().map(|item| item.title.clone()).collect();
+    let (tx, rx) = channel();
+    // 중지 핸들은 사용하지 않음 (Ctrl+C 시 yt-dlp도 함께 종료됨)
+    let _handle = run_queue(items, jobs, tx);
+
+    let multi = MultiProgress::new();
+    let mut bars: HashMap<usize, ProgressBar> = HashMap::new();
+
+    for event in rx {
+        match event {
+            QueueEvent::Updated(idx, state, progress) => {
+                if state.is_finished() {
+                    if let Some(bar) = bars.remove(&idx) {
+                        bar.finish_and_clear();
+                    }
+                    let line = match &state {
+                        ItemState::Completed => rust_i18n::t!("cli.completed", title = titles[idx]),
+                        ItemState::Failed(e, _) => rust_i18n::t!("cli.failed", error = format!("{} ({})", titles[idx], e)),
+                        _ => rust_i18n::t!("main.download_stopped"),
+                    };
+                    let mut line = format!("[{}/{}] {}", progress.finished(), progress.total, line);
+                    // 실패 원인 확인용 yt-dlp stderr
+                    if let ItemState::Failed(e, stderr) = &state {
+                        for stderr_line in stderr {
+                            line.push_str("\n    ");
+                            line.push_str(stderr_line);
+                        }
+                        if matches!(e, Error::AuthRequired(_)) {
+                            line.push('\n');
+                            line.push_str(&rust_i18n::t!("cli.auth_hint"));
+                        }
+                    }
+                    // 터미널이 아니면 MultiProgress 출력이 숨겨지므로 직접 출력
+                    if multi.is_hidden() {
+                        eprintln!("{}", line);
+                    } else {
+                        let _ = multi.println(line);
+                    }
+                    continue;
+                }
+
+                let bar = bars.entry(idx).or_insert_with(|| {
+                    let bar = multi.add(ProgressBar::new(100).with_style(progress_style()));
+                    bar.set_prefix(titles[idx].clone());
+                    bar
+                });
+                match state {
+                    ItemState::Downloading(progress) => {
+                        bar.set_position(progress.percent as u64);
+                        bar.set_message(format!("{} ETA {}", progress.speed_string(), progress.eta_string()));
+                    }
+                    ItemState::Waiting(secs) => bar.set_message(rust_i18n::t!("queue.waiting", secs = secs).to_string()),
+                    ItemState::Converting => bar.set_message(rust_i18n::t!("main.converting").to_string()),
+                    _ => {}
+                }
+            }
+            QueueEvent::Finished(progress) => {
+                return (progress.completed, progress.total - progress.completed);
+            }
+        }
+    }
+    (0, titles.len())
+}
+
+fn progress_style() -> ProgressStyle {
+    ProgressStyle::with_template("{prefix:30!} [{bar:30}] {pos:>3}% {msg}")
+        .unwrap()
+        .progress_chars("=> ")
+}
+
+/// "sponsor,intro" 형식의 SponsorBlock 분류 목록 파싱 ("none"이면 빈 목록)
+fn parse_sponsor_categories(spec: &str) -> Result<Vec<SponsorCategory>, String> {
+    if spec.trim() == "none" {
+        return Ok(Vec::new());
+    }
+    spec.split(',')
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .map(|name| SponsorCategory::parse(name).ok_or_else(|| name.to_string()))
+        .collect()
+}
+
+/// "10" 또는 "5-30" 형식의 대기 시간 파싱 ("0"이면 대기하지 않음)
+fn parse_sleep_interval(spec: &str) -> Result<Option<(u32, u32)>, String> {
+    let parse = |s: &str| s.trim().parse::<u32>().map_err(|_| spec.to_string());
+    let (min, max) = match spec.split_once('-') {
+        Some((min, max)) => (parse(min)?, parse(max)?),
+        None => {
+            let secs = parse(spec)?;
+            (secs, secs)
+        }
+    };
+    if min > max || max > MAX_SLEEP_SECS {
+        return Err(spec.to_string());
+    }
+    Ok(Some((min, max)).filter(|_| max > 0))
+}
+
+/// "1,3,5-8" 형식의 항목 번호 범위 목록 파싱
+fn parse_item_spec(spec: &str) -> Result<Vec<RangeInclusive<usize>>, String> {
+    let mut ranges = Vec::new();
+    for part in spec.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        let parse = |s: &str| s.trim().parse::<usize>()
+            .ok()
+            .filter(|n| *n > 0)
+            .ok_or_else(|| part.to_string());
+
+        if let Some((start, end)) = part.split_once('-') {
+            let (start, end) = (parse(start)?, parse(end)?);
+            if start > end {
+                return Err(part.to_string());
+            }
+            ranges.push(start..=end);
+        } else {
+            let item = parse(part)?;
+            ranges.push(item..=item);
+        }
+    }
+
+    if ranges.is_empty() {
+        return Err(spec.to_string());
+    }
+    Ok(ranges)
+}
+
+/// 항목 수에 맞춰 범위를 잘라낸 뒤 번호 목록으로 펼침 ("1-999999999"도 항목 수만큼만)
+fn select_items(ranges: &[RangeInclusive<usize>], count: usize) -> Vec<usize> {
+    ranges.iter()
+        .flat_map(|r| *r.start()..=(*r.end()).min(count))
+        .collect()
+}
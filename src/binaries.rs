@@ -0,0 +1,237 @@
+//! yt-dlp/ffmpeg 실행 파일 위치 결정
+//!
+//! 실행 파일 경로는 모두 여기서 정한다. 찾는 순서:
+//! 1. 지정 경로 (`RUST_YT_YTDLP` / `RUST_YT_FFMPEG` 환경 변수, 없으면 설정의 `ytdlp_path` / `ffmpeg_path`)
+//! 2. 앱이 관리하는 폴더 (`<data_local_dir>/rust-yt`, 초기화 시 받는 위치)
+//! 3. 시스템 PATH (GUI로 실행하면 빠지기 쉬운 Homebrew/pipx 경로 포함)
+
+use crate::error::Error;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// yt-dlp 경로를 강제로 지정하는 환경 변수 (테스트/직접 설치용)
+pub const YTDLP_PATH_ENV: &str = "RUST_YT_YTDLP";
+/// ffmpeg 경로를 강제로 지정하는 환경 변수
+pub const FFMPEG_PATH_ENV: &str = "RUST_YT_FFMPEG";
+
+/// 설정에서 지정한 (yt-dlp, ffmpeg) 경로
+static CONFIGURED: Mutex<(Option<PathBuf>, Option<PathBuf>)> = Mutex::new((None, None));
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tool {
+    YtDlp,
+    Ffmpeg,
+}
+
+impl Tool {
+    pub fn name(self) -> &'static str {
+        match self {
+            Tool::YtDlp => "yt-dlp",
+            Tool::Ffmpeg => "ffmpeg",
+        }
+    }
+
+    /// 실행 파일 이름 (Windows는 `.exe`)
+    pub fn file_name(self) -> String {
+        format!("{}{}", self.name(), std::env::consts::EXE_SUFFIX)
+    }
+
+    fn env_var(self) -> &'static str {
+        match self {
+            Tool::YtDlp => YTDLP_PATH_ENV,
+            Tool::Ffmpeg => FFMPEG_PATH_ENV,
+        }
+    }
+
+    fn not_found(self, detail: String) -> Error {
+        match self {
+            Tool::YtDlp => Error::YtDlpNotFound(detail),
+            Tool::Ffmpeg => Error::FfmpegMissing,
+        }
+    }
+}
+
+/// 실행 파일을 찾은 위치
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinarySource {
+    Override,
+    AppManaged,
+    SystemPath,
+}
+
+impl BinarySource {
+    pub fn label(self) -> String {
+        match self {
+            BinarySource::Override => rust_i18n::t!("binaries.override").to_string(),
+            BinarySource::AppManaged => rust_i18n::t!("binaries.app_managed").to_string(),
+            BinarySource::SystemPath => rust_i18n::t!("binaries.system_path").to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedBinary {
+    pub tool: Tool,
+    pub path: PathBuf,
+    pub source: BinarySource,
+}
+
+impl ResolvedBinary {
+    /// "yt-dlp: <경로> (<위치>)" 형태의 안내 문구
+    pub fn describe(&self) -> String {
+        rust_i18n::t!(
+            "binaries.resolved",
+            tool = self.tool.name(),
+            path = self.path.display().to_string(),
+            source = self.source.label()
+        ).to_string()
+    }
+}
+
+/// 앱이 실행 파일을 내려받아 관리하는 폴더
+pub fn app_dir() -> PathBuf {
+    dirs::data_local_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("rust-yt")
+}
+
+/// 앱 관리 폴더 안의 실행 파일 경로 (존재 여부와 무관)
+pub fn app_managed_path(tool: Tool) -> PathBuf {
+    app_dir().join(tool.file_name())
+}
+
+/// 설정 파일의 실행 파일 경로 적용 (환경 변수가 있으면 그쪽이 우선)
+pub fn set_configured_paths(ytdlp: Option<PathBuf>, ffmpeg: Option<PathBuf>) {
+    *CONFIGURED.lock().unwrap() = (ytdlp, ffmpeg);
+}
+
+/// 지정 경로 (환경 변수 또는 설정). 지정하지 않았으면 None
+pub fn override_path(tool: Tool) -> Option<PathBuf> {
+    if let Some(path) = std::env::var_os(tool.env_var()).filter(|p| !p.is_empty()) {
+        return Some(PathBuf::from(path));
+    }
+    let configured = CONFIGURED.lock().unwrap();
+    match tool {
+        Tool::YtDlp => configured.0.clone(),
+        Tool::Ffmpeg => configured.1.clone(),
+    }
+}
+
+/// 정해진 순서대로 실행 파일 찾기
+pub fn resolve(tool: Tool) -> Result<ResolvedBinary, Error> {
+    resolve_in(tool, override_path(tool), &app_dir(), &search_dirs())
+}
+
+/// 찾지 못한 실행 파일 안내 문구
+pub fn missing_message(tool: Tool) -> String {
+    match override_path(tool) {
+        Some(path) => rust_i18n::t!("binaries.override_missing", tool = tool.name(), path = path.display().to_string()).to_string(),
+        None => rust_i18n::t!("binaries.not_found", tool = tool.name()).to_string(),
+    }
+}
+
+/// 시스템 PATH와 GUI 실행 시 PATH에서 빠지기 쉬운 설치 위치
+fn search_dirs() -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = std::env::var_os("PATH")
+        .map(|p| std::env::split_paths(&p).collect())
+        .unwrap_or_default();
+
+    #[cfg(target_os = "macos")]
+    paths.extend(["/opt/homebrew/bin", "/usr/local/bin"].map(PathBuf::from));
+    // pipx 기본 설치 위치
+    #[cfg(not(target_os = "windows"))]
+    if let Some(home) = dirs::home_dir() {
+        paths.push(home.join(".local").join("bin"));
+    }
+
+    paths
+}
+
+fn resolve_in(tool: Tool, override_path: Option<PathBuf>, app_dir: &Path, search: &[PathBuf]) -> Result<ResolvedBinary, Error> {
+    let found = |path: PathBuf, source| Ok(ResolvedBinary { tool, path, source });
+
+    // 지정 경로가 잘못됐으면 다른 위치로 넘어가지 않고 바로 알림
+    if let Some(path) = override_path {
+        if is_executable(&path) {
+            return found(path, BinarySource::Override);
+        }
+        return Err(tool.not_found(path.display().to_string()));
+    }
+
+    let file_name = tool.file_name();
+    let managed = app_dir.join(&file_name);
+    if is_executable(&managed) {
+        return found(managed, BinarySource::AppManaged);
+    }
+
+    match search.iter().map(|dir| dir.join(&file_name)).find(|path| is_executable(path)) {
+        Some(path) => found(path, BinarySource::SystemPath),
+        None => Err(tool.not_found(rust_i18n::t!("binaries.not_found", tool = tool.name()).to_string())),
+    }
+}
+
+fn is_executable(path: &Path) -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        path.metadata().is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+    }
+    #[cfg(not(unix))]
+    {
+        path.is_file()
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+
+    fn make_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("rust-yt-binaries-{}-{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn make_tool(dir: &Path, tool: Tool, mode: u32) -> PathBuf {
+        let path = dir.join(tool.file_name());
+        fs::write(&path, "#!/bin/sh\n").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(mode)).unwrap();
+        path
+    }
+
+    #[test]
+    fn resolves_in_defined_order() {
+        let app = make_dir("app");
+        let first = make_dir("path-1");
+        let second = make_dir("path-2");
+        let search = vec![first.clone(), second.clone()];
+
+        // PATH에서는 앞쪽 폴더 우선, 실행 권한 없는 파일은 건너뜀
+        make_tool(&first, Tool::YtDlp, 0o644);
+        let system = make_tool(&second, Tool::YtDlp, 0o755);
+        let resolved = resolve_in(Tool::YtDlp, None, &app, &search).unwrap();
+        assert_eq!((resolved.path, resolved.source), (system.clone(), BinarySource::SystemPath));
+
+        // 앱 관리 폴더가 PATH보다 우선
+        let managed = make_tool(&app, Tool::YtDlp, 0o755);
+        let resolved = resolve_in(Tool::YtDlp, None, &app, &search).unwrap();
+        assert_eq!((resolved.path, resolved.source), (managed, BinarySource::AppManaged));
+
+        // 지정 경로가 가장 우선
+        let resolved = resolve_in(Tool::YtDlp, Some(system.clone()), &app, &search).unwrap();
+        assert_eq!((resolved.path, resolved.source), (system, BinarySource::Override));
+    }
+
+    #[test]
+    fn missing_binaries_are_reported() {
+        let app = make_dir("empty");
+        let missing = app.join("nope");
+
+        let err = resolve_in(Tool::YtDlp, Some(missing.clone()), &app, &[]).unwrap_err();
+        assert_eq!(err, Error::YtDlpNotFound(missing.display().to_string()));
+        assert_eq!(resolve_in(Tool::Ffmpeg, None, &app, std::slice::from_ref(&app)), Err(Error::FfmpegMissing));
+    }
+}
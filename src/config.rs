@@ -1,7 +1,10 @@
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
-use crate::downloader::DownloadFormat;
+use crate::auth::AuthOptions;
+use crate::downloader::{DownloadFormat, SponsorBlockOptions, SubtitleOptions, VideoCodec};
+use crate::error::Error;
+use crate::template::DEFAULT_OUTPUT_TEMPLATE;
 
 /// 앱 설정
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -9,62 +12,129 @@ pub struct AppConfig {
     pub download_dir: Option<PathBuf>,
     pub format: String,
     pub audio_quality: String,
+    #[serde(default)]
+    pub sample_rate: Option<u32>,
+    #[serde(default)]
+    pub bit_depth: Option<u8>,
+    #[serde(default)]
+    pub max_height: Option<u32>,
+    #[serde(default)]
+    pub max_fps: Option<u32>,
+    #[serde(default)]
+    pub video_codec: VideoCodec,
+    #[serde(default = "default_output_template")]
+    pub output_template: String,
+    #[serde(default)]
+    pub playlist_subfolder: bool, // 플레이리스트는 제목 폴더 + 트랙 번호로 저장
     #[serde(default = "default_language")]
     pub language: String,
+    #[serde(default = "default_max_concurrent_downloads")]
+    pub max_concurrent_downloads: usize,
+    #[serde(default)]
+    pub proxy: Option<String>, // 모든 네트워크 요청에 쓸 HTTP/SOCKS 프록시
+    #[serde(default)]
+    pub rate_limit: Option<u32>, // KiB/s
+    #[serde(default)]
+    pub sleep_interval: Option<(u32, u32)>, // 다운로드 사이 대기 (최소, 최대 초)
+    #[serde(default)]
+    pub ytdlp_path: Option<PathBuf>, // 직접 지정한 yt-dlp (없으면 앱 폴더 → PATH 순서로 찾음)
+    #[serde(default)]
+    pub ffmpeg_path: Option<PathBuf>,
+    #[serde(default)]
+    pub use_system_tools: bool, // yt-dlp/ffmpeg를 내려받거나 업데이트하지 않음
+    // 아래는 TOML 테이블로 저장되므로 마지막에 둔다
+    #[serde(default)]
+    pub subtitles: SubtitleOptions,
+    #[serde(default)]
+    pub sponsorblock: SponsorBlockOptions,
+    #[serde(default)]
+    pub auth: AuthOptions,
+}
+
+fn default_output_template() -> String {
+    DEFAULT_OUTPUT_TEMPLATE.to_string()
 }
 
 fn default_language() -> String {
     "auto".to_string()
 }
 
+fn default_max_concurrent_downloads() -> usize {
+    3
+}
+
 impl Default for AppConfig {
     fn default() -> Self {
         Self {
             download_dir: None,
             format: "mp3".to_string(),
             audio_quality: "320K".to_string(),
+            sample_rate: None,
+            bit_depth: None,
+            max_height: None,
+            max_fps: None,
+            video_codec: VideoCodec::Any,
+            output_template: default_output_template(),
+            playlist_subfolder: false,
             language: "auto".to_string(),
+            max_concurrent_downloads: default_max_concurrent_downloads(),
+            proxy: None,
+            rate_limit: None,
+            sleep_interval: None,
+            ytdlp_path: None,
+            ffmpeg_path: None,
+            use_system_tools: false,
+            subtitles: SubtitleOptions::default(),
+            sponsorblock: SponsorBlockOptions::default(),
+            auth: AuthOptions::default(),
         }
     }
 }
 
+/// 설정 폴더를 강제로 지정하는 환경 변수 (테스트/휴대용 실행용)
+pub const CONFIG_DIR_ENV: &str = "RUST_YT_CONFIG_DIR";
+
+/// 설정 파일들이 저장되는 폴더
+pub(crate) fn config_dir() -> PathBuf {
+    if let Some(dir) = std::env::var_os(CONFIG_DIR_ENV).filter(|d| !d.is_empty()) {
+        return PathBuf::from(dir);
+    }
+    dirs::config_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("rust-yt")
+}
+
 impl AppConfig {
     /// 설정 파일 경로
     fn config_path() -> PathBuf {
-        dirs::config_dir()
-            .unwrap_or_else(|| PathBuf::from("."))
-            .join("rust-yt")
-            .join("config.toml")
+        config_dir().join("config.toml")
     }
 
     /// 설정 로드 (파일이 없으면 기본값 반환)
     pub fn load() -> Self {
         let path = Self::config_path();
-        if path.exists() {
-            if let Ok(content) = fs::read_to_string(&path) {
-                if let Ok(config) = toml::from_str(&content) {
-                    return config;
-                }
-            }
+        if path.exists()
+            && let Ok(content) = fs::read_to_string(&path)
+            && let Ok(config) = toml::from_str(&content)
+        {
+            return config;
         }
         Self::default()
     }
 
     /// 설정 저장
-    pub fn save(&self) -> Result<(), String> {
+    pub fn save(&self) -> Result<(), Error> {
         let path = Self::config_path();
         
         // 디렉토리 생성
         if let Some(parent) = path.parent() {
-            fs::create_dir_all(parent)
-                .map_err(|e| format!("설정 폴더 생성 실패: {}", e))?;
+            fs::create_dir_all(parent)?;
         }
         
         let content = toml::to_string_pretty(self)
-            .map_err(|e| format!("설정 직렬화 실패: {}", e))?;
+            .map_err(|e| Error::Parse(e.to_string()))?;
         
-        fs::write(&path, content)
-            .map_err(|e| format!("설정 파일 저장 실패: {}", e))?;
+        fs::write(&path, content)?;
         
         Ok(())
     }
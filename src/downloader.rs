@@ -1,12 +1,20 @@
+use std::collections::VecDeque;
 use std::io::{BufRead, BufReader};
 use std::process::{Command, Stdio};
 use std::sync::mpsc::{Receiver, Sender};
 use std::sync::{Arc, Mutex};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::thread;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use crate::auth::AuthOptions;
+use crate::binaries::{resolve, Tool};
+use crate::error::{classify_ytdlp_stderr, Error};
+use crate::network::proxy_args;
+use crate::template::resolve_output_path;
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum DownloadFormat {
     Mp3,
     Wav,
@@ -16,21 +24,582 @@ pub enum DownloadFormat {
     Webm,
 }
 
-#[derive(Debug, Clone)]
+impl DownloadFormat {
+    /// 오디오 추출 형식인지
+    pub fn is_audio(&self) -> bool {
+        matches!(self, DownloadFormat::Mp3 | DownloadFormat::Wav | DownloadFormat::M4a | DownloadFormat::Flac)
+    }
+
+    /// 무손실 오디오 형식인지 (비트레이트 대신 샘플레이트/비트 깊이 사용)
+    pub fn is_lossless(&self) -> bool {
+        matches!(self, DownloadFormat::Wav | DownloadFormat::Flac)
+    }
+}
+
+/// MP4/WEBM 선호 비디오 코덱
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VideoCodec {
+    #[default]
+    Any,
+    H264,
+    Vp9,
+    Av1,
+}
+
+impl VideoCodec {
+    pub const ALL: [VideoCodec; 4] = [VideoCodec::Any, VideoCodec::H264, VideoCodec::Vp9, VideoCodec::Av1];
+
+    /// 컨테이너에 넣을 수 있는 코덱인지 (WEBM은 H.264, MP4는 VP9를 병합할 수 없음)
+    pub fn supports(self, format: &DownloadFormat) -> bool {
+        match format {
+            DownloadFormat::Webm => matches!(self, VideoCodec::Any | VideoCodec::Vp9 | VideoCodec::Av1),
+            _ => matches!(self, VideoCodec::Any | VideoCodec::H264 | VideoCodec::Av1),
+        }
+    }
+
+    /// 컨테이너에 맞는 코덱 목록
+    pub fn for_format(format: &DownloadFormat) -> Vec<VideoCodec> {
+        Self::ALL.into_iter().filter(|codec| codec.supports(format)).collect()
+    }
+
+    /// yt-dlp 형식 필터 (`vcodec` 접두어)
+    fn filter(&self) -> &'static str {
+        match self {
+            VideoCodec::Any => "",
+            VideoCodec::H264 => "[vcodec^=avc1]",
+            VideoCodec::Vp9 => "[vcodec^=vp09]",
+            VideoCodec::Av1 => "[vcodec^=av01]",
+        }
+    }
+
+    /// 화면 표시용 이름 (Any는 호출 측에서 번역)
+    pub fn label(&self) -> &'static str {
+        match self {
+            VideoCodec::Any => "Any",
+            VideoCodec::H264 => "H.264",
+            VideoCodec::Vp9 => "VP9",
+            VideoCodec::Av1 => "AV1",
+        }
+    }
+}
+
+/// 자막 파일 형식
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SubtitleFormat {
+    #[default]
+    Srt,
+    Vtt,
+}
+
+impl SubtitleFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SubtitleFormat::Srt => "srt",
+            SubtitleFormat::Vtt => "vtt",
+        }
+    }
+}
+
+/// 자막 받기 설정
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SubtitleOptions {
+    pub enabled: bool,
+    #[serde(default)]
+    pub languages: Vec<String>, // 예: ["en", "ko"], 비어 있으면 모든 언어
+    #[serde(default)]
+    pub include_auto: bool,     // 자동 생성 자막도 받기 (언어를 지정했을 때만)
+    #[serde(default)]
+    pub format: SubtitleFormat, // 파일로 저장할 때 형식
+    #[serde(default)]
+    pub embed: bool,            // MP4/WEBM에 넣기 (오디오 형식은 항상 파일로 저장)
+}
+
+impl SubtitleOptions {
+    /// "en, ko" 같은 입력을 언어 목록으로
+    pub fn parse_languages(input: &str) -> Vec<String> {
+        input.split([',', ' '])
+            .map(str::trim)
+            .filter(|l| !l.is_empty())
+            .map(str::to_string)
+            .collect()
+    }
+
+    /// yt-dlp 인자
+    fn args(&self, format: &DownloadFormat) -> Vec<String> {
+        if !self.enabled {
+            return Vec::new();
+        }
+
+        // 언어 없이 자동 생성 자막을 받으면 자동 번역 자막까지 전부 받으므로 언어를 지정했을 때만
+        let mut args = vec!["--write-subs".to_string()];
+        if self.include_auto && !self.languages.is_empty() {
+            args.push("--write-auto-subs".to_string());
+        }
+
+        let languages = if self.languages.is_empty() {
+            "all,-live_chat".to_string()
+        } else {
+            self.languages.join(",")
+        };
+        args.extend_from_slice(&["--sub-langs".to_string(), languages]);
+
+        if self.embed && !format.is_audio() {
+            args.push("--embed-subs".to_string());
+        } else {
+            args.extend_from_slice(&["--convert-subs".to_string(), self.format.as_str().to_string()]);
+        }
+        args
+    }
+}
+
+/// SponsorBlock 구간 분류
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SponsorCategory {
+    Sponsor,
+    Intro,
+    Outro,
+    Selfpromo,
+    Preview,
+    Filler,
+    Interaction,
+    MusicOfftopic,
+    PoiHighlight, // 하이라이트 지점 (표시만 가능)
+}
+
+impl SponsorCategory {
+    pub const ALL: [SponsorCategory; 9] = [
+        SponsorCategory::Sponsor,
+        SponsorCategory::Intro,
+        SponsorCategory::Outro,
+        SponsorCategory::Selfpromo,
+        SponsorCategory::Preview,
+        SponsorCategory::Filler,
+        SponsorCategory::Interaction,
+        SponsorCategory::MusicOfftopic,
+        SponsorCategory::PoiHighlight,
+    ];
+
+    /// yt-dlp/SponsorBlock 분류 이름
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SponsorCategory::Sponsor => "sponsor",
+            SponsorCategory::Intro => "intro",
+            SponsorCategory::Outro => "outro",
+            SponsorCategory::Selfpromo => "selfpromo",
+            SponsorCategory::Preview => "preview",
+            SponsorCategory::Filler => "filler",
+            SponsorCategory::Interaction => "interaction",
+            SponsorCategory::MusicOfftopic => "music_offtopic",
+            SponsorCategory::PoiHighlight => "poi_highlight",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|c| c.as_str() == name.trim())
+    }
+
+    /// 잘라낼 수 있는 분류인지 (하이라이트는 한 지점이라 불가)
+    pub fn removable(&self) -> bool {
+        *self != SponsorCategory::PoiHighlight
+    }
+}
+
+/// SponsorBlock 설정: 잘라낼 분류와 챕터로 표시할 분류
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SponsorBlockOptions {
+    #[serde(default)]
+    pub remove: Vec<SponsorCategory>,
+    #[serde(default)]
+    pub mark: Vec<SponsorCategory>,
+}
+
+impl SponsorBlockOptions {
+    /// yt-dlp 인자 (둘 다 고른 분류는 잘라내기만 함)
+    fn args(&self) -> Vec<String> {
+        let join = |categories: Vec<&SponsorCategory>| categories.iter()
+            .map(|c| c.as_str())
+            .collect::<Vec<_>>()
+            .join(",");
+
+        let remove: Vec<&SponsorCategory> = self.remove.iter().filter(|c| c.removable()).collect();
+        let mark: Vec<&SponsorCategory> = self.mark.iter().filter(|c| !remove.contains(c)).collect();
+
+        let mut args = Vec::new();
+        if !remove.is_empty() {
+            args.extend_from_slice(&["--sponsorblock-remove".to_string(), join(remove)]);
+        }
+        if !mark.is_empty() {
+            args.extend_from_slice(&["--sponsorblock-mark".to_string(), join(mark)]);
+        }
+        args
+    }
+}
+
+/// 영상 일부 구간만 받기
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TimeRange {
+    pub start: f64,       // 초
+    pub end: Option<f64>, // None이면 끝까지
+}
+
+impl TimeRange {
+    /// 입력칸 값으로 구간 생성 (둘 다 비어 있으면 None = 전체)
+    ///
+    /// `duration`을 알면 영상 길이를 넘는지도 확인한다.
+    pub fn parse(start: &str, end: &str, duration: Option<f64>) -> Result<Option<Self>, Error> {
+        if start.trim().is_empty() && end.trim().is_empty() {
+            return Ok(None);
+        }
+
+        let parse = |input: &str| -> Result<Option<f64>, Error> {
+            if input.trim().is_empty() {
+                return Ok(None);
+            }
+            crate::playlist::parse_timestamp(input)
+                .map(Some)
+                .ok_or_else(|| Error::InvalidTimeRange(input.trim().to_string()))
+        };
+        let range = TimeRange {
+            start: parse(start)?.unwrap_or(0.0),
+            end: parse(end)?,
+        };
+
+        range.validate(duration)?;
+        Ok(Some(range))
+    }
+
+    /// 시작 < 끝, 영상 길이 이내인지 확인
+    pub fn validate(&self, duration: Option<f64>) -> Result<(), Error> {
+        let describe = || format!(
+            "{} - {}",
+            crate::playlist::format_timestamp(self.start),
+            self.end.map(crate::playlist::format_timestamp).unwrap_or_default()
+        );
+
+        if self.end.is_some_and(|end| end <= self.start) {
+            return Err(Error::InvalidTimeRange(describe()));
+        }
+        if let Some(duration) = duration
+            && (self.start >= duration || self.end.is_some_and(|end| end > duration))
+        {
+            return Err(Error::InvalidTimeRange(format!(
+                "{} > {}",
+                describe(),
+                crate::playlist::format_timestamp(duration)
+            )));
+        }
+        Ok(())
+    }
+
+    /// `--download-sections` 값 ("*90-120", "*90-inf")
+    fn section(&self) -> String {
+        match self.end {
+            Some(end) => format!("*{}-{}", self.start, end),
+            None => format!("*{}-inf", self.start),
+        }
+    }
+}
+
+/// MP4/WEBM 최대 해상도 선택지 (세로 픽셀)
+pub const VIDEO_HEIGHTS: [u32; 7] = [2160, 1440, 1080, 720, 480, 360, 240];
+/// MP4/WEBM 최대 프레임레이트 선택지
+pub const VIDEO_FPS: [u32; 2] = [60, 30];
+
+/// MP3/M4A 고정 비트레이트 선택지
+pub const AUDIO_BITRATES: [&str; 5] = ["128K", "160K", "192K", "256K", "320K"];
+/// MP3/M4A VBR 선택지 (yt-dlp `--audio-quality` 0~9, 0이 최고 품질)
+pub const AUDIO_VBR_LEVELS: [&str; 10] = ["0", "1", "2", "3", "4", "5", "6", "7", "8", "9"];
+/// WAV/FLAC 샘플레이트 선택지 (Hz)
+pub const SAMPLE_RATES: [u32; 4] = [44_100, 48_000, 88_200, 96_000];
+/// WAV/FLAC 비트 깊이 선택지
+pub const BIT_DEPTHS: [u8; 2] = [16, 24];
+/// 다운로드 속도 제한 선택지 (KiB/s)
+pub const RATE_LIMITS: [u32; 6] = [256, 512, 1024, 2048, 5120, 10240];
+/// 다운로드 사이 대기 최대 초
+pub const MAX_SLEEP_SECS: u32 = 600;
+
+/// "500K", "2M", "1.5M" 형식의 속도 제한 → KiB/s (단위가 없으면 KiB/s)
+pub fn parse_rate_limit(input: &str) -> Option<u32> {
+    let input = input.trim().to_uppercase();
+    let (number, scale) = match input.strip_suffix('M') {
+        Some(number) => (number, 1024.0),
+        None => (input.strip_suffix('K').unwrap_or(&input), 1.0),
+    };
+    let kib = (number.trim().parse::<f64>().ok()? * scale).round();
+    (kib >= 1.0 && kib <= u32::MAX as f64).then_some(kib as u32)
+}
+
+/// 속도 제한 표시 ("512.0 KiB/s", "2.0 MiB/s")
+pub fn format_rate_limit(kib: u32) -> String {
+    format!("{}/s", format_bytes(kib as u64 * 1024))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DownloadConfig {
     pub url: String,
     pub format: DownloadFormat,
-    pub audio_quality: String,
+    pub audio_quality: String, // MP3/M4A: "320K" 같은 비트레이트 또는 VBR "0"~"9"
+    #[serde(default)]
+    pub sample_rate: Option<u32>, // WAV/FLAC, None이면 원본 유지
+    #[serde(default)]
+    pub bit_depth: Option<u8>,    // WAV/FLAC, None이면 원본 유지
+    #[serde(default)]
+    pub max_height: Option<u32>,  // MP4/WEBM, None이면 최고 해상도
+    #[serde(default)]
+    pub max_fps: Option<u32>,     // MP4/WEBM, None이면 제한 없음
+    #[serde(default)]
+    pub video_codec: VideoCodec,  // MP4/WEBM
+    #[serde(default)]
+    pub format_id: Option<String>, // 직접 고른 yt-dlp 형식 (예: "137+140"), 화질/코덱 설정보다 우선
     pub output_dir: PathBuf,
+    #[serde(default)]
+    pub output_name: Option<PathBuf>, // output_dir 기준 상대 경로 (`template::expand_template` 결과), None이면 "<제목>.<확장자>"
+    #[serde(default)]
+    pub subtitles: SubtitleOptions,
+    #[serde(default)]
+    pub split_chapters: bool,            // 챕터마다 파일 하나씩 (원본 파일도 남음)
+    #[serde(default)]
+    pub skipped_chapters: Vec<(f64, f64)>, // 나눌 때 빼버릴 챕터 구간 (초)
+    #[serde(default)]
+    pub time_range: Option<TimeRange>,   // 일부 구간만 받기
+    #[serde(default)]
+    pub precise_cuts: bool,              // 구간 경계를 키프레임에 맞춰 다시 인코딩 (느림)
+    #[serde(default)]
+    pub sponsorblock: SponsorBlockOptions,
+    #[serde(default)]
+    pub auth: AuthOptions,
+    #[serde(default)]
+    pub proxy: Option<String>,
+    #[serde(default)]
+    pub rate_limit: Option<u32>,              // KiB/s
+    #[serde(default)]
+    pub sleep_interval: Option<(u32, u32)>,   // 큐에서 앞 항목 다음에 기다릴 시간 (최소, 최대 초, 그 사이 무작위), 스케줄러만 사용
+}
+
+impl DownloadConfig {
+    /// 나머지 옵션은 기본값 (320K, 원본 음질/화질, 자막·챕터·구간 없음)
+    pub fn new(url: String, format: DownloadFormat, output_dir: PathBuf) -> Self {
+        Self {
+            url,
+            format,
+            audio_quality: "320K".to_string(),
+            sample_rate: None,
+            bit_depth: None,
+            max_height: None,
+            max_fps: None,
+            video_codec: VideoCodec::Any,
+            format_id: None,
+            output_dir,
+            output_name: None,
+            subtitles: SubtitleOptions::default(),
+            split_chapters: false,
+            skipped_chapters: Vec::new(),
+            time_range: None,
+            precise_cuts: false,
+            sponsorblock: SponsorBlockOptions::default(),
+            auth: AuthOptions::default(),
+            proxy: None,
+            rate_limit: None,
+            sleep_interval: None,
+        }
+    }
+}
+
+/// 챕터별 파일 경로 템플릿: "<폴더>/<영상 파일명>/01 - <챕터 제목>.<확장자>"
+fn chapter_output_template(output_template: &Path) -> PathBuf {
+    let stem = output_template.file_name()
+        .map(|name| name.to_string_lossy().trim_end_matches(".%(ext)s").to_string())
+        .unwrap_or_default();
+    output_template.with_file_name(stem).join("%(section_number)02d - %(section_title)s.%(ext)s")
+}
+
+/// 챕터 나누기 인자
+fn chapter_args(config: &DownloadConfig, output_template: &Path) -> Vec<String> {
+    if !config.split_chapters {
+        return Vec::new();
+    }
+
+    let mut args = vec![
+        "--split-chapters".to_string(),
+        "-o".to_string(),
+        format!("chapter:{}", chapter_output_template(output_template).to_string_lossy()),
+    ];
+    // 뺀 챕터는 나누기 전에 잘라내므로 남은 챕터 번호는 1부터 이어진다
+    for (start, end) in &config.skipped_chapters {
+        args.extend_from_slice(&["--remove-chapters".to_string(), format!("*{}-{}", start, end)]);
+    }
+    args
+}
+
+/// MP4/WEBM용 yt-dlp 형식 선택자 생성
+///
+/// 조건에 맞는 스트림이 없으면 코덱 → 분리 스트림 → 해상도 순으로 조건을 풀어가며 받는다.
+pub fn video_format_selector(config: &DownloadConfig) -> String {
+    let (video_ext, audio_ext) = match config.format {
+        DownloadFormat::Webm => ("[ext=webm]", ""),
+        _ => ("[ext=mp4]", "[ext=m4a]"),
+    };
+
+    let mut limits = String::new();
+    if let Some(height) = config.max_height {
+        limits.push_str(&format!("[height<={}]", height));
+    }
+    if let Some(fps) = config.max_fps {
+        limits.push_str(&format!("[fps<={}]", fps));
+    }
+
+    let mut candidates = Vec::new();
+    if config.video_codec != VideoCodec::Any && config.video_codec.supports(&config.format) {
+        // 코덱을 지정하면 컨테이너는 병합 단계에서 맞춘다 (병합할 수 없는 조합은 무시)
+        candidates.push(format!("bestvideo{}{}+bestaudio", limits, config.video_codec.filter()));
+    }
+    candidates.push(format!("bestvideo{}{}+bestaudio{}", video_ext, limits, audio_ext));
+    candidates.push(format!("best{}{}", video_ext, limits));
+    if !limits.is_empty() {
+        candidates.push(format!("best{}", limits));
+    }
+    candidates.push("best".to_string());
+
+    candidates.join("/")
+}
+
+/// WAV/FLAC 변환 시 ffmpeg에 넘길 인자 (`--postprocessor-args`용)
+fn lossless_postprocessor_args(config: &DownloadConfig) -> Option<String> {
+    let mut ffmpeg_args = Vec::new();
+    if let Some(rate) = config.sample_rate {
+        ffmpeg_args.push(format!("-ar {}", rate));
+    }
+    match (&config.format, config.bit_depth) {
+        (DownloadFormat::Wav, Some(24)) => ffmpeg_args.push("-c:a pcm_s24le".to_string()),
+        (DownloadFormat::Wav, Some(_)) => ffmpeg_args.push("-c:a pcm_s16le".to_string()),
+        (DownloadFormat::Flac, Some(24)) => ffmpeg_args.push("-sample_fmt s32".to_string()),
+        (DownloadFormat::Flac, Some(_)) => ffmpeg_args.push("-sample_fmt s16".to_string()),
+        _ => {}
+    }
+
+    if ffmpeg_args.is_empty() {
+        None
+    } else {
+        Some(format!("ExtractAudio:{}", ffmpeg_args.join(" ")))
+    }
+}
+
+/// 실패 시 함께 전달할 stderr 마지막 줄 수
+pub const STDERR_TAIL_LINES: usize = 30;
+
+/// yt-dlp 진행률 템플릿 출력 줄 앞에 붙는 표식
+const PROGRESS_PREFIX: &str = "[rust-yt-progress] ";
+
+/// 다운로드 진행 정보 (yt-dlp progress hook 값)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DownloadProgress {
+    pub percent: f64,
+    pub downloaded_bytes: u64,
+    pub total_bytes: Option<u64>,   // 정확한 크기 또는 추정치
+    pub speed: Option<f64>,         // bytes/s
+    pub eta: Option<u64>,           // seconds
+    pub fragment_index: Option<u64>,
+    pub fragment_count: Option<u64>,
+}
+
+impl DownloadProgress {
+    /// 속도 표시용 문자열 (예: "1.5 MiB/s")
+    pub fn speed_string(&self) -> String {
+        match self.speed {
+            Some(speed) => format!("{}/s", format_bytes(speed as u64)),
+            None => String::new(),
+        }
+    }
+
+    /// 남은 시간 표시용 문자열 (예: "03:25")
+    pub fn eta_string(&self) -> String {
+        match self.eta {
+            Some(eta) if eta >= 3600 => format!("{}:{:02}:{:02}", eta / 3600, eta / 60 % 60, eta % 60),
+            Some(eta) => format!("{:02}:{:02}", eta / 60, eta % 60),
+            None => "--:--".to_string(),
+        }
+    }
+}
+
+/// 바이트 수를 사람이 읽기 쉬운 단위로 변환
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+/// `--progress-template`로 출력된 JSON 한 줄
+#[derive(Debug, Deserialize)]
+struct YtDlpProgress {
+    #[serde(default)]
+    status: Option<String>,
+    #[serde(default)]
+    downloaded_bytes: Option<f64>,
+    #[serde(default)]
+    total_bytes: Option<f64>,
+    #[serde(default)]
+    total_bytes_estimate: Option<f64>,
+    #[serde(default)]
+    speed: Option<f64>,
+    #[serde(default)]
+    eta: Option<f64>,
+    #[serde(default)]
+    fragment_index: Option<u64>,
+    #[serde(default)]
+    fragment_count: Option<u64>,
+}
+
+/// 진행률 템플릿 줄 파싱 (다른 출력이면 None)
+pub fn parse_progress_line(line: &str) -> Option<DownloadProgress> {
+    let json = line.trim().strip_prefix(PROGRESS_PREFIX.trim_end())?;
+    let raw: YtDlpProgress = serde_json::from_str(json.trim()).ok()?;
+
+    let downloaded_bytes = raw.downloaded_bytes.unwrap_or(0.0).max(0.0) as u64;
+    let total_bytes = raw.total_bytes
+        .or(raw.total_bytes_estimate)
+        .filter(|t| *t > 0.0)
+        .map(|t| t as u64);
+
+    let percent = if raw.status.as_deref() == Some("finished") {
+        100.0
+    } else if let Some(total) = total_bytes {
+        downloaded_bytes as f64 / total as f64 * 100.0
+    } else if let (Some(idx), Some(count)) = (raw.fragment_index, raw.fragment_count.filter(|c| *c > 0)) {
+        idx as f64 / count as f64 * 100.0
+    } else {
+        0.0
+    };
+
+    Some(DownloadProgress {
+        percent: percent.clamp(0.0, 100.0),
+        downloaded_bytes,
+        total_bytes,
+        speed: raw.speed,
+        eta: raw.eta.map(|e| e.max(0.0) as u64),
+        fragment_index: raw.fragment_index,
+        fragment_count: raw.fragment_count,
+    })
 }
 
 #[derive(Debug, Clone)]
 pub enum DownloadStatus {
     Starting(String),     // message
-    Progress(f64, String), // percent, speed/status
+    Progress(DownloadProgress),
     Converting,
     Completed(String),    // filename
-    Failed(String),       // error message
+    Failed(Error, Vec<String>), // error, stderr 마지막 줄들
     Stopped,              // [NEW] 중단됨
 }
 
@@ -40,34 +609,28 @@ pub fn download_video(
     tx: Sender<DownloadStatus>,
     stop_signal: Receiver<()> // [NEW] 중지 신호
 ) {
-    let ytdlp = crate::playlist::get_ytdlp_path();
+    let ytdlp = match resolve(Tool::YtDlp) {
+        Ok(found) => found.path,
+        Err(e) => {
+            let _ = tx.send(DownloadStatus::Failed(e, Vec::new()));
+            return;
+        }
+    };
+    // ffmpeg가 없으면 변환 단계에서 yt-dlp가 오류를 내고 FfmpegMissing으로 분류됨
+    let ffmpeg = resolve(Tool::Ffmpeg).ok();
     
     // 파일명 살균 및 템플릿 설정
     let sanitized_title = sanitize_filename(&title);
-    
-    // ffmpeg 경로 설정을 위한 PATH 업데이트
-    #[cfg(target_os = "windows")]
-    let new_path = {
-        let app_dir = dirs::data_local_dir()
-            .unwrap_or_else(|| PathBuf::from("."))
-            .join("rust-yt");
-        let current_path = std::env::var("PATH").unwrap_or_default();
-        format!("{};{}", app_dir.display(), current_path)
-    };
-    #[cfg(target_os = "macos")]
-    let new_path = {
-        let current_path = std::env::var("PATH").unwrap_or_default();
-        // GUI 앱은 PATH가 제대로 설정되지 않을 수 있으므로 homebrew 경로 추가
-        format!("{}:/opt/homebrew/bin:/usr/local/bin:{}", current_path, std::env::var("HOME").unwrap_or_default() + "/.cargo/bin")
-    };
-    #[cfg(all(not(target_os = "windows"), not(target_os = "macos")))]
-    let new_path = std::env::var("PATH").unwrap_or_default();
 
-    let output_template = match config.format {
-        DownloadFormat::Mp3 | DownloadFormat::Wav | DownloadFormat::M4a | DownloadFormat::Flac => {
-            config.output_dir.join(format!("{}.%(ext)s", sanitized_title))
-        }
-        _ => config.output_dir.join(format!("{}.%(ext)s", sanitized_title)), // Video formats mainly
+    let output_template = match &config.output_name {
+        Some(name) => match resolve_output_path(&config.output_dir, name) {
+            Ok(path) => path,
+            Err(e) => {
+                let _ = tx.send(DownloadStatus::Failed(e, Vec::new()));
+                return;
+            }
+        },
+        None => config.output_dir.join(format!("{}.%(ext)s", sanitized_title)),
     };
 
     let output_str = output_template.to_string_lossy().to_string();
@@ -76,12 +639,26 @@ pub fn download_video(
         "--no-playlist".to_string(),
         "--newline".to_string(),
         "--progress".to_string(),
+        "--progress-template".to_string(),
+        format!("download:{}%(progress)j", PROGRESS_PREFIX),
         "--embed-thumbnail".to_string(), // [NEW] 썸네일 포함
         "--add-metadata".to_string(),    // [NEW] 메타데이터 포함
         "-o".to_string(),
         output_str,
     ];
 
+    let lossless_args = lossless_postprocessor_args(&config);
+    let chapter_args = chapter_args(&config, &output_template);
+    let video_selector = config.format_id.clone()
+        .unwrap_or_else(|| video_format_selector(&config));
+
+    // 오디오 형식도 고른 스트림에서 추출
+    if config.format.is_audio()
+        && let Some(format_id) = &config.format_id
+    {
+        args.extend_from_slice(&["-f".to_string(), format_id.clone()]);
+    }
+
     match config.format {
         DownloadFormat::Mp3 => {
             args.extend_from_slice(&[
@@ -100,6 +677,7 @@ pub fn download_video(
             args.extend_from_slice(&[
                 "-x".to_string(),
                 "--audio-format".to_string(), "m4a".to_string(),
+                "--audio-quality".to_string(), config.audio_quality,
             ]);
         }
         DownloadFormat::Flac => {
@@ -110,26 +688,51 @@ pub fn download_video(
         }
         DownloadFormat::Mp4 => {
             args.extend_from_slice(&[
-                "-f".to_string(), "bestvideo[ext=mp4]+bestaudio[ext=m4a]/best[ext=mp4]/best".to_string(),
+                "-f".to_string(), video_selector,
                 "--merge-output-format".to_string(), "mp4".to_string(),
             ]);
         }
         DownloadFormat::Webm => {
             args.extend_from_slice(&[
-                "-f".to_string(), "bestvideo[ext=webm]+bestaudio/best".to_string(),
+                "-f".to_string(), video_selector,
                 "--merge-output-format".to_string(), "webm".to_string(),
             ]);
         }
     }
 
+    if let Some(pp_args) = lossless_args {
+        args.extend_from_slice(&["--postprocessor-args".to_string(), pp_args]);
+    }
+
+    args.extend(config.subtitles.args(&config.format));
+    args.extend(chapter_args);
+
+    args.extend(config.sponsorblock.args());
+    args.extend(config.auth.args());
+    args.extend(proxy_args(config.proxy.as_deref()));
+
+    if let Some(ffmpeg) = ffmpeg {
+        args.extend_from_slice(&["--ffmpeg-location".to_string(), ffmpeg.path.to_string_lossy().to_string()]);
+    }
+
+    if let Some(rate) = config.rate_limit {
+        args.extend_from_slice(&["-r".to_string(), format!("{}K", rate)]);
+    }
+
+    if let Some(range) = &config.time_range {
+        args.extend_from_slice(&["--download-sections".to_string(), range.section()]);
+        if config.precise_cuts {
+            args.push("--force-keyframes-at-cuts".to_string());
+        }
+    }
+
     // URL은 마지막에 추가
     args.push(config.url);
 
-    let _ = tx.send(DownloadStatus::Starting("다운로드 시작...".to_string()));
+    let _ = tx.send(DownloadStatus::Starting(rust_i18n::t!("queue.download_started").to_string()));
 
     let mut command = Command::new(&ytdlp);
-    command.env("PATH", &new_path)
-           .args(&args)
+    command.args(&args)
            .stdout(Stdio::piped())
            .stderr(Stdio::piped());
 
@@ -143,7 +746,7 @@ pub fn download_video(
     let child = match command.spawn() {
             Ok(c) => c,
             Err(e) => {
-                let _ = tx.send(DownloadStatus::Failed(format!("실행 실패: {}", e)));
+                let _ = tx.send(DownloadStatus::Failed(Error::YtDlpNotFound(format!("{}: {}", ytdlp.display(), e)), Vec::new()));
                 return;
             }
         };
@@ -169,31 +772,36 @@ pub fn download_video(
     });
 
     // 2. 메인 로직: stdout 읽기
-    // Mutex를 잠깐 잠그고 stdout을 가져옴 (option take)
-    let stdout = {
+    // Mutex를 잠깐 잠그고 stdout/stderr를 가져옴 (option take)
+    let (stdout, stderr) = {
         let mut c = child_shared.lock().unwrap();
-        c.stdout.take()
+        (c.stdout.take(), c.stderr.take())
     };
 
+    // 3. stderr 스레드: 파이프가 가득 차 멈추지 않도록 계속 읽고 마지막 줄만 보관
+    let stderr_reader = thread::spawn(move || {
+        let mut tail = VecDeque::with_capacity(STDERR_TAIL_LINES);
+        if let Some(err) = stderr {
+            for line in BufReader::new(err).lines().map_while(Result::ok) {
+                if tail.len() == STDERR_TAIL_LINES {
+                    tail.pop_front();
+                }
+                tail.push_back(line);
+            }
+        }
+        Vec::from(tail)
+    });
+
     if let Some(out) = stdout {
         let reader = BufReader::new(out);
-        for line in reader.lines() {
-            if let Ok(line) = line {
-                if line.contains("[download]") && line.contains("%") {
-                    if let Some(percent_str) = line.split_whitespace().find(|s| s.ends_with('%')) {
-                        if let Ok(percent) = percent_str.trim_end_matches('%').parse::<f64>() {
-                            let speed = line.split_whitespace()
-                                .find(|s| s.ends_with("/s"))
-                                .unwrap_or("")
-                                .to_string();
-                            let _ = tx.send(DownloadStatus::Progress(percent, speed));
-                        }
-                    }
-                }
-                
-                if line.contains("[ExtractAudio]") || line.contains("[Merger]") {
-                    let _ = tx.send(DownloadStatus::Converting);
-                }
+        for line in reader.lines().map_while(Result::ok) {
+            if let Some(progress) = parse_progress_line(&line) {
+                let _ = tx.send(DownloadStatus::Progress(progress));
+                continue;
+            }
+
+            if line.contains("[ExtractAudio]") || line.contains("[Merger]") {
+                let _ = tx.send(DownloadStatus::Converting);
             }
         }
     }
@@ -207,6 +815,7 @@ pub fn download_video(
 
     // 중지 신호가 왔는지 확인
     let was_stopped = stopped.load(Ordering::SeqCst);
+    let stderr_tail = stderr_reader.join().unwrap_or_default();
 
     match status_result {
         Ok(status) => {
@@ -217,20 +826,25 @@ pub fn download_video(
                 let _ = tx.send(DownloadStatus::Stopped);
             } else {
                 // 실제 오류
-                let _ = tx.send(DownloadStatus::Failed("다운로드 실패".to_string()));
+                let error = if stderr_tail.is_empty() {
+                    Error::YtDlp(status.to_string())
+                } else {
+                    classify_ytdlp_stderr(&stderr_tail.join("\n"))
+                };
+                let _ = tx.send(DownloadStatus::Failed(error, stderr_tail));
             }
         }
-        Err(_) => {
+        Err(e) => {
             if was_stopped {
                 let _ = tx.send(DownloadStatus::Stopped);
             } else {
-                let _ = tx.send(DownloadStatus::Failed("프로세스 대기 오류".to_string()));
+                let _ = tx.send(DownloadStatus::Failed(Error::from(e), stderr_tail));
             }
         }
     }
 }
 
-fn sanitize_filename(filename: &str) -> String {
+pub(crate) fn sanitize_filename(filename: &str) -> String {
     filename
         .chars()
         .filter(|c| c.is_alphanumeric() || c.is_whitespace() || "-_()[].,!&'".contains(*c))
@@ -242,3 +856,198 @@ fn sanitize_filename(filename: &str) -> String {
         .trim()
         .to_string()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // `--progress-template "download:[rust-yt-progress] %(progress)j"` 출력 예시
+    const DOWNLOADING: &str = r#"[rust-yt-progress] {"status": "downloading", "downloaded_bytes": 1048576, "total_bytes": 4194304, "tmpfilename": "song.webm.part", "filename": "song.webm", "eta": 3, "speed": 1048576.0, "elapsed": 1.0021, "ctx_id": null, "_eta_str": "00:03", "_speed_str": "   1.00MiB/s", "_percent_str": " 25.0%", "_total_bytes_str": "   4.00MiB", "_total_bytes_estimate_str": "N/A", "_downloaded_bytes_str": "   1.00MiB", "_elapsed_str": "00:00:01", "_default_template": " 25.0% of    4.00MiB at    1.00MiB/s ETA 00:03"}"#;
+    const ESTIMATE: &str = r#"[rust-yt-progress] {"status": "downloading", "downloaded_bytes": 512, "total_bytes": null, "total_bytes_estimate": 2048.0, "eta": null, "speed": null, "elapsed": 0.1}"#;
+    const FRAGMENTS: &str = r#"[rust-yt-progress] {"status": "downloading", "downloaded_bytes": 3000, "fragment_index": 12, "fragment_count": 48, "eta": 95, "speed": 250000.5}"#;
+    const FINISHED: &str = r#"[rust-yt-progress] {"status": "finished", "downloaded_bytes": 4194304, "total_bytes": 4194304, "filename": "song.webm", "elapsed": 4.2, "ctx_id": null}"#;
+
+    #[test]
+    fn parses_downloading_line() {
+        let progress = parse_progress_line(DOWNLOADING).unwrap();
+        assert_eq!(progress.percent, 25.0);
+        assert_eq!(progress.downloaded_bytes, 1_048_576);
+        assert_eq!(progress.total_bytes, Some(4_194_304));
+        assert_eq!(progress.speed, Some(1_048_576.0));
+        assert_eq!(progress.eta, Some(3));
+        assert_eq!(progress.speed_string(), "1.0 MiB/s");
+        assert_eq!(progress.eta_string(), "00:03");
+    }
+
+    #[test]
+    fn falls_back_to_total_estimate() {
+        let progress = parse_progress_line(ESTIMATE).unwrap();
+        assert_eq!(progress.total_bytes, Some(2048));
+        assert_eq!(progress.percent, 25.0);
+        assert_eq!(progress.speed, None);
+        assert_eq!(progress.eta_string(), "--:--");
+    }
+
+    #[test]
+    fn uses_fragments_without_total() {
+        let progress = parse_progress_line(FRAGMENTS).unwrap();
+        assert_eq!(progress.total_bytes, None);
+        assert_eq!(progress.fragment_index, Some(12));
+        assert_eq!(progress.fragment_count, Some(48));
+        assert_eq!(progress.percent, 25.0);
+        assert_eq!(progress.eta_string(), "01:35");
+    }
+
+    #[test]
+    fn finished_is_full_progress() {
+        let progress = parse_progress_line(FINISHED).unwrap();
+        assert_eq!(progress.percent, 100.0);
+    }
+
+    #[test]
+    fn ignores_other_output() {
+        assert!(parse_progress_line("[youtube] dQw4w9WgXcQ: Downloading webpage").is_none());
+        assert!(parse_progress_line("[download]  25.0% of 4.00MiB at 1.00MiB/s ETA 00:03").is_none());
+        assert!(parse_progress_line("[ExtractAudio] Destination: song.mp3").is_none());
+        assert!(parse_progress_line("[rust-yt-progress] not json").is_none());
+    }
+
+    #[test]
+    fn default_video_selector_prefers_container() {
+        assert_eq!(
+            video_format_selector(&DownloadConfig::new(String::new(), DownloadFormat::Mp4, PathBuf::new())),
+            "bestvideo[ext=mp4]+bestaudio[ext=m4a]/best[ext=mp4]/best"
+        );
+        assert_eq!(
+            video_format_selector(&DownloadConfig::new(String::new(), DownloadFormat::Webm, PathBuf::new())),
+            "bestvideo[ext=webm]+bestaudio/best[ext=webm]/best"
+        );
+    }
+
+    #[test]
+    fn video_selector_applies_limits_and_codec() {
+        let mut config = DownloadConfig::new(String::new(), DownloadFormat::Mp4, PathBuf::new());
+        config.max_height = Some(720);
+        config.max_fps = Some(30);
+        config.video_codec = VideoCodec::H264;
+        assert_eq!(
+            video_format_selector(&config),
+            "bestvideo[height<=720][fps<=30][vcodec^=avc1]+bestaudio\
+             /bestvideo[ext=mp4][height<=720][fps<=30]+bestaudio[ext=m4a]\
+             /best[ext=mp4][height<=720][fps<=30]\
+             /best[height<=720][fps<=30]\
+             /best"
+        );
+
+        // WEBM에 넣을 수 없는 코덱은 선택자에 넣지 않음
+        config.format = DownloadFormat::Webm;
+        config.max_fps = None;
+        assert_eq!(
+            video_format_selector(&config),
+            "bestvideo[ext=webm][height<=720]+bestaudio/best[ext=webm][height<=720]/best[height<=720]/best"
+        );
+    }
+
+    #[test]
+    fn subtitle_args_embed_only_into_video() {
+        let mut subs = SubtitleOptions {
+            enabled: true,
+            languages: SubtitleOptions::parse_languages("en, ko"),
+            include_auto: true,
+            format: SubtitleFormat::Vtt,
+            embed: true,
+        };
+        assert_eq!(
+            subs.args(&DownloadFormat::Mp4),
+            ["--write-subs", "--write-auto-subs", "--sub-langs", "en,ko", "--embed-subs"]
+        );
+        // 오디오는 넣을 곳이 없으므로 파일로 저장
+        assert_eq!(
+            subs.args(&DownloadFormat::Mp3),
+            ["--write-subs", "--write-auto-subs", "--sub-langs", "en,ko", "--convert-subs", "vtt"]
+        );
+
+        // 모든 언어를 받을 때는 자동 생성(자동 번역) 자막 제외
+        subs.languages.clear();
+        subs.embed = false;
+        assert_eq!(
+            subs.args(&DownloadFormat::Webm),
+            ["--write-subs", "--sub-langs", "all,-live_chat", "--convert-subs", "vtt"]
+        );
+        assert!(SubtitleOptions::default().args(&DownloadFormat::Mp4).is_empty());
+    }
+
+    #[test]
+    fn chapter_args_split_into_video_folder() {
+        let mut config = DownloadConfig::new(String::new(), DownloadFormat::Mp3, PathBuf::new());
+        assert!(chapter_args(&config, Path::new("/music/Mix.%(ext)s")).is_empty());
+
+        config.split_chapters = true;
+        config.skipped_chapters = vec![(0.0, 12.5), (300.0, 420.0)];
+        assert_eq!(
+            chapter_args(&config, Path::new("/music/Mix.%(ext)s")),
+            [
+                "--split-chapters",
+                "-o",
+                "chapter:/music/Mix/%(section_number)02d - %(section_title)s.%(ext)s",
+                "--remove-chapters",
+                "*0-12.5",
+                "--remove-chapters",
+                "*300-420",
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_and_validates_time_ranges() {
+        assert_eq!(TimeRange::parse("", " ", Some(60.0)).unwrap(), None);
+        assert_eq!(
+            TimeRange::parse("1:02:03", "1:02:33.5", Some(10_800.0)).unwrap(),
+            Some(TimeRange { start: 3723.0, end: Some(3753.5) })
+        );
+        // 시작만 주면 끝까지, 끝만 주면 처음부터
+        assert_eq!(TimeRange::parse("90", "", None).unwrap().unwrap().section(), "*90-inf");
+        assert_eq!(TimeRange::parse("", "0:30", Some(60.0)).unwrap().unwrap().section(), "*0-30");
+
+        for (start, end) in [("1:00", "0:30"), ("0:10", "0:10"), ("abc", ""), ("1:75", ""), ("0:50", "2:00")] {
+            assert!(
+                matches!(TimeRange::parse(start, end, Some(100.0)), Err(Error::InvalidTimeRange(_))),
+                "{} - {}", start, end
+            );
+        }
+    }
+
+    #[test]
+    fn sponsorblock_remove_wins_over_mark() {
+        assert!(SponsorBlockOptions::default().args().is_empty());
+
+        let options = SponsorBlockOptions {
+            remove: vec![SponsorCategory::Sponsor, SponsorCategory::MusicOfftopic, SponsorCategory::PoiHighlight],
+            mark: vec![SponsorCategory::Sponsor, SponsorCategory::Intro, SponsorCategory::PoiHighlight],
+        };
+        assert_eq!(
+            options.args(),
+            ["--sponsorblock-remove", "sponsor,music_offtopic", "--sponsorblock-mark", "intro,poi_highlight"]
+        );
+        assert_eq!(SponsorCategory::parse("music_offtopic"), Some(SponsorCategory::MusicOfftopic));
+        assert_eq!(SponsorCategory::parse("nope"), None);
+    }
+
+    #[test]
+    fn parses_rate_limits() {
+        assert_eq!(parse_rate_limit("500K"), Some(500));
+        assert_eq!(parse_rate_limit("2M"), Some(2048));
+        assert_eq!(parse_rate_limit("1.5m"), Some(1536));
+        assert_eq!(parse_rate_limit("300"), Some(300));
+        assert_eq!(parse_rate_limit("0"), None);
+        assert_eq!(parse_rate_limit("fast"), None);
+        assert_eq!(format_rate_limit(2048), "2.0 MiB/s");
+    }
+
+    #[test]
+    fn formats_byte_units() {
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(1536), "1.5 KiB");
+        assert_eq!(format_bytes(5 * 1024 * 1024 * 1024), "5.0 GiB");
+    }
+}
@@ -0,0 +1,182 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// 라이브러리 공통 오류
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Error {
+    /// yt-dlp 실행 파일을 찾거나 실행할 수 없음
+    YtDlpNotFound(String),
+    /// ffmpeg가 없어 변환/병합 불가
+    FfmpegMissing,
+    /// 삭제/비공개/지역 제한 등으로 영상을 받을 수 없음
+    VideoUnavailable(String),
+    /// 로그인(쿠키)이 있어야 볼 수 있는 영상 (연령 제한, 멤버십, 봇 확인 등)
+    AuthRequired(String),
+    /// 요청이 너무 많음 (HTTP 429)
+    RateLimited,
+    /// 디스크 공간 부족
+    DiskFull,
+    /// 네트워크/HTTP 오류
+    Network(String),
+    /// 파일 읽기/쓰기 오류
+    Io(String),
+    /// JSON/TOML 등 파싱 오류
+    Parse(String),
+    /// 압축 해제 오류
+    Archive(String),
+    /// 출력 파일명 템플릿이 잘못되었거나 저장 폴더 밖을 가리킴
+    InvalidTemplate(String),
+    /// 구간 시작/끝 시각이 잘못됨
+    InvalidTimeRange(String),
+    /// 받은 파일의 SHA-256이 공개된 값과 다르거나 확인할 수 없음
+    ChecksumMismatch(String),
+    /// 프록시 주소 형식이 잘못됨
+    InvalidProxy(String),
+    /// 분류되지 않은 yt-dlp 오류
+    YtDlp(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let msg = match self {
+            Error::YtDlpNotFound(detail) => rust_i18n::t!("errors.ytdlp_not_found", detail = detail),
+            Error::FfmpegMissing => rust_i18n::t!("errors.ffmpeg_missing"),
+            Error::VideoUnavailable(detail) => rust_i18n::t!("errors.video_unavailable", detail = detail),
+            Error::AuthRequired(detail) => rust_i18n::t!("errors.auth_required", detail = detail),
+            Error::RateLimited => rust_i18n::t!("errors.rate_limited"),
+            Error::DiskFull => rust_i18n::t!("errors.disk_full"),
+            Error::Network(detail) => rust_i18n::t!("errors.network", detail = detail),
+            Error::Io(detail) => rust_i18n::t!("errors.io", detail = detail),
+            Error::Parse(detail) => rust_i18n::t!("errors.parse", detail = detail),
+            Error::Archive(detail) => rust_i18n::t!("errors.archive", detail = detail),
+            Error::InvalidTemplate(detail) => rust_i18n::t!("errors.invalid_template", detail = detail),
+            Error::InvalidTimeRange(detail) => rust_i18n::t!("errors.invalid_time_range", detail = detail),
+            Error::ChecksumMismatch(detail) => rust_i18n::t!("errors.checksum_mismatch", detail = detail),
+            Error::InvalidProxy(detail) => rust_i18n::t!("errors.invalid_proxy", detail = detail),
+            Error::YtDlp(detail) => rust_i18n::t!("errors.ytdlp", detail = detail),
+        };
+        f.write_str(&msg)
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        // ENOSPC (unix) / ERROR_DISK_FULL, ERROR_HANDLE_DISK_FULL (windows)
+        let disk_full = e.kind() == std::io::ErrorKind::StorageFull
+            || matches!(e.raw_os_error(), Some(28) if cfg!(unix))
+            || matches!(e.raw_os_error(), Some(39 | 112) if cfg!(windows));
+        if disk_full {
+            Error::DiskFull
+        } else {
+            Error::Io(e.to_string())
+        }
+    }
+}
+
+impl From<reqwest::Error> for Error {
+    fn from(e: reqwest::Error) -> Self {
+        if e.status().map(|s| s.as_u16()) == Some(429) {
+            Error::RateLimited
+        } else {
+            Error::Network(e.to_string())
+        }
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(e: serde_json::Error) -> Self {
+        Error::Parse(e.to_string())
+    }
+}
+
+/// yt-dlp stderr 내용으로 오류 종류 분류
+pub fn classify_ytdlp_stderr(stderr: &str) -> Error {
+    let lower = stderr.to_lowercase();
+    // 마지막 "ERROR:" 줄이 가장 구체적인 원인
+    let detail = stderr.lines()
+        .rev()
+        .find(|l| l.trim_start().starts_with("ERROR:"))
+        .or_else(|| stderr.lines().rev().find(|l| !l.trim().is_empty()))
+        .unwrap_or("")
+        .trim()
+        .trim_start_matches("ERROR:")
+        .trim()
+        .to_string();
+
+    if lower.contains("http error 429") || lower.contains("too many requests") {
+        Error::RateLimited
+    } else if lower.contains("no space left on device") || lower.contains("not enough space on the disk") {
+        Error::DiskFull
+    } else if lower.contains("ffmpeg not found") || lower.contains("ffprobe and ffmpeg not found") || lower.contains("ffmpeg is not installed") {
+        Error::FfmpegMissing
+    } else if lower.contains("sign in to confirm")
+        || lower.contains("sign in if you've been granted access")
+        || lower.contains("members-only content")
+        || lower.contains("available to this channel's members")
+        || lower.contains("use --cookies")
+    {
+        Error::AuthRequired(detail)
+    } else if lower.contains("video unavailable")
+        || lower.contains("this video is unavailable")
+        || lower.contains("private video")
+        || lower.contains("has been removed")
+        || lower.contains("is not available in your country")
+    {
+        Error::VideoUnavailable(detail)
+    } else if lower.contains("unable to download webpage")
+        || lower.contains("getaddrinfo failed")
+        || lower.contains("name or service not known")
+        || lower.contains("timed out")
+        || lower.contains("connection reset")
+        || lower.contains("connection refused")
+    {
+        Error::Network(detail)
+    } else {
+        Error::YtDlp(detail)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_common_ytdlp_errors() {
+        assert_eq!(
+            classify_ytdlp_stderr("ERROR: [youtube] abc: Unable to download webpage: HTTP Error 429: Too Many Requests"),
+            Error::RateLimited
+        );
+        assert_eq!(
+            classify_ytdlp_stderr("ERROR: [youtube] abc: Video unavailable. This video has been removed by the uploader"),
+            Error::VideoUnavailable("[youtube] abc: Video unavailable. This video has been removed by the uploader".to_string())
+        );
+        assert_eq!(
+            classify_ytdlp_stderr("ERROR: Postprocessing: ffprobe and ffmpeg not found. Please install or provide the path using --ffmpeg-location"),
+            Error::FfmpegMissing
+        );
+        assert_eq!(
+            classify_ytdlp_stderr("ERROR: unable to write data: [Errno 28] No space left on device"),
+            Error::DiskFull
+        );
+        assert!(matches!(
+            classify_ytdlp_stderr("ERROR: [youtube] abc: Sign in to confirm your age. This video may be inappropriate for some users."),
+            Error::AuthRequired(_)
+        ));
+        assert!(matches!(
+            classify_ytdlp_stderr("ERROR: [youtube] abc: Private video. Sign in if you've been granted access to this video"),
+            Error::AuthRequired(_)
+        ));
+        assert!(matches!(
+            classify_ytdlp_stderr("ERROR: [youtube] abc: Unable to download webpage: <urlopen error [Errno -2] Name or service not known>"),
+            Error::Network(_)
+        ));
+    }
+
+    #[test]
+    fn keeps_last_error_line_for_unknown_errors() {
+        let stderr = "WARNING: something minor\nERROR: first\nERROR: Unsupported URL: https://example.com\n";
+        assert_eq!(classify_ytdlp_stderr(stderr), Error::YtDlp("Unsupported URL: https://example.com".to_string()));
+    }
+}
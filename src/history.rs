@@ -0,0 +1,90 @@
+use crate::downloader::DownloadFormat;
+use crate::error::Error;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// 동시에 끝난 다운로드들이 파일을 덮어쓰지 않도록 잠금
+static HISTORY_LOCK: Mutex<()> = Mutex::new(());
+
+/// 완료된 다운로드 기록 한 건
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub id: String,
+    pub format: DownloadFormat,
+    pub title: String,
+    pub completed_at: u64, // unix seconds
+}
+
+/// 다운로드 기록 (config.toml 옆의 history.json)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DownloadHistory {
+    pub entries: Vec<HistoryEntry>,
+}
+
+impl DownloadHistory {
+    /// 기록 파일 경로
+    fn history_path() -> PathBuf {
+        crate::config::config_dir().join("history.json")
+    }
+
+    /// 기록 로드 (파일이 없거나 깨졌으면 빈 기록)
+    pub fn load() -> Self {
+        fs::read_to_string(Self::history_path())
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    /// 기록 저장
+    pub fn save(&self) -> Result<(), Error> {
+        let path = Self::history_path();
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let content = serde_json::to_string_pretty(self)?;
+        fs::write(&path, content)?;
+
+        Ok(())
+    }
+
+    /// 해당 영상을 해당 형식으로 받은 적이 있는지
+    pub fn contains(&self, id: &str, format: &DownloadFormat) -> bool {
+        self.entries.iter().any(|e| e.id == id && &e.format == format)
+    }
+
+    /// 해당 영상을 받은 형식 목록
+    pub fn formats_for(&self, id: &str) -> Vec<DownloadFormat> {
+        self.entries.iter()
+            .filter(|e| e.id == id)
+            .map(|e| e.format.clone())
+            .collect()
+    }
+
+    /// 완료된 다운로드를 기록 파일에 추가
+    pub fn record(id: &str, format: &DownloadFormat, title: &str) -> Result<(), Error> {
+        if id.is_empty() {
+            return Ok(());
+        }
+
+        let _guard = HISTORY_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let mut history = Self::load();
+        let completed_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+
+        history.entries.retain(|e| !(e.id == id && &e.format == format));
+        history.entries.push(HistoryEntry {
+            id: id.to_string(),
+            format: format.clone(),
+            title: title.to_string(),
+            completed_at,
+        });
+        history.save()
+    }
+}
@@ -1,52 +1,135 @@
+use crate::archive;
+use crate::binaries::{self, BinarySource, ResolvedBinary, Tool};
+use crate::error::{classify_ytdlp_stderr, Error};
+use crate::network::{http_client, proxy_args};
 use std::fs;
-use std::io::copy;
 use std::path::{Path, PathBuf};
 use std::process::Command;
-use zip::ZipArchive;
+use std::time::Duration;
 
 #[derive(Debug, Clone)]
 pub enum InitStatus {
     Starting(String),
     Downloading(f64, String), // percent, filename
     Extracting(String),
+    /// SHA-256 확인 완료 (파일 이름, 해시). 배포처가 체크섬을 공개하지 않으면 해시는 None
+    Verified(String, Option<String>),
+    /// 자동 설치 없이는 준비할 수 없는 실행 파일 (`binaries::missing_message`로 안내)
+    Missing(Vec<Tool>),
     Completed,
     Failed(String),
 }
 
-// Assuming ValidatedResult is defined elsewhere, e.g., type ValidatedResult<T> = Result<T, String>;
-type ValidatedResult<T> = Result<T, String>;
+type ValidatedResult<T> = Result<T, Error>;
 
-pub fn init_dependencies(tx: std::sync::mpsc::Sender<InitStatus>) {
-    let app_dir = get_app_dir();
-    if !app_dir.exists() {
-        if let Err(e) = fs::create_dir_all(&app_dir) {
-            let _ = tx.send(InitStatus::Failed(rust_i18n::t!("initialization.folder_error", error = e.to_string()).to_string()));
-            return;
+impl InitStatus {
+    /// `Verified` 결과 안내 문구 (해시는 앞 12자리만)
+    pub fn verification_message(file: &str, hash: Option<&str>) -> String {
+        match hash {
+            Some(hash) => rust_i18n::t!("initialization.verified", file = file, hash = &hash[..hash.len().min(12)]).to_string(),
+            None => rust_i18n::t!("initialization.unverified", file = file).to_string(),
         }
     }
+}
 
-    // 1. Check yt-dlp
-    let ytdlp_path = get_ytdlp_path(&app_dir);
-    if !ytdlp_path.exists() {
-        if let Err(e) = download_ytdlp(&app_dir, &tx) {
-            let _ = tx.send(InitStatus::Failed(rust_i18n::t!("initialization.ytdlp_download_fail", error = e).to_string()));
-            return;
-        }
+/// 받을 파일과 같은 릴리스에 공개된 SHA-256 목록 주소
+#[derive(Debug, Clone)]
+pub struct Artifact {
+    pub url: String,
+    pub sums_url: Option<String>, // "<해시>  <파일 이름>" 줄 목록, 또는 이 파일 하나의 해시만 담은 ".sha256"
+}
+
+impl Artifact {
+    /// 체크섬 목록에서 찾을 파일 이름 (URL 마지막 부분)
+    fn file_name(&self) -> &str {
+        self.url.rsplit('/').next().unwrap_or(&self.url)
     }
+}
 
-    // 2. Check ffmpeg
-    let ffmpeg_path = get_ffmpeg_path(&app_dir);
-    if !ffmpeg_path.exists() {
-        if let Err(e) = download_ffmpeg(&app_dir, &tx) {
-            let _ = tx.send(InitStatus::Failed(rust_i18n::t!("initialization.ffmpeg_download_fail", error = e).to_string()));
-            return;
-        }
+/// `sha256sum` 형식 목록에서 `file_name`의 해시 찾기
+///
+/// 파일 하나용 ".sha256"처럼 해시 한 줄만 있으면 그 해시를 쓴다.
+fn find_checksum(sums: &str, file_name: &str) -> Option<String> {
+    let is_hash = |hash: &str| hash.len() == 64 && hash.chars().all(|c| c.is_ascii_hexdigit());
+    let mut lines = sums.lines().map(str::trim).filter(|line| !line.is_empty());
+    if let (Some(line), None) = (lines.next(), lines.next())
+        && is_hash(line)
+    {
+        return Some(line.to_lowercase());
+    }
+
+    sums.lines().find_map(|line| {
+        let (hash, name) = line.trim().split_once(char::is_whitespace)?;
+        // "*name"은 바이너리 모드 표기
+        let name = name.trim().trim_start_matches('*');
+        (name == file_name && is_hash(hash))
+            .then(|| hash.to_lowercase())
+    })
+}
+
+/// 의존성 확인/설치 (`proxy`는 다운로드와 yt-dlp 업데이트 확인에 사용)
+///
+/// `auto_download`가 false면 아무것도 내려받지 않고 설치된 실행 파일의 버전만 확인한다.
+pub fn init_dependencies(tx: std::sync::mpsc::Sender<InitStatus>, proxy: Option<String>, auto_download: bool) {
+    let proxy = proxy.as_deref();
+    let ytdlp = resolve_working(Tool::YtDlp, ytdlp_version);
+    let ffmpeg = resolve_working(Tool::Ffmpeg, check_ffmpeg);
+
+    // 자동 설치를 껐거나 지정 경로가 틀렸으면 앱 폴더에 받아도 쓰이지 않으므로 바로 알림
+    let missing: Vec<Tool> = [(Tool::YtDlp, ytdlp.is_err()), (Tool::Ffmpeg, ffmpeg.is_err())]
+        .into_iter()
+        .filter(|&(tool, is_missing)| is_missing && (!auto_download || binaries::override_path(tool).is_some()))
+        .map(|(tool, _)| tool)
+        .collect();
+    if !missing.is_empty() {
+        let _ = tx.send(InitStatus::Missing(missing));
+        return;
+    }
+
+    let app_dir = binaries::app_dir();
+    if (ytdlp.is_err() || ffmpeg.is_err())
+        && !app_dir.exists()
+        && let Err(e) = fs::create_dir_all(&app_dir)
+    {
+        let _ = tx.send(InitStatus::Failed(rust_i18n::t!("initialization.folder_error", error = e.to_string()).to_string()));
+        return;
     }
 
+    // 1. Check yt-dlp (지정 경로, 앱 폴더, PATH 어디에도 없을 때만 앱 폴더에 설치)
+    let ytdlp = match ytdlp {
+        Ok(found) => found,
+        Err(_) => match download_ytdlp(&app_dir, &tx, proxy) {
+            Ok(()) => installed(Tool::YtDlp),
+            Err(e) => {
+                let _ = tx.send(InitStatus::Failed(rust_i18n::t!("initialization.ytdlp_download_fail", error = e).to_string()));
+                return;
+            }
+        },
+    };
+    let _ = tx.send(InitStatus::Starting(ytdlp.describe()));
+
+    // 2. Check ffmpeg
+    let ffmpeg = match ffmpeg {
+        Ok(found) => found,
+        Err(_) => match download_ffmpeg(&app_dir, &tx, proxy) {
+            Ok(()) => installed(Tool::Ffmpeg),
+            Err(e) => {
+                let _ = tx.send(InitStatus::Failed(rust_i18n::t!("initialization.ffmpeg_download_fail", error = e).to_string()));
+                return;
+            }
+        },
+    };
+    let _ = tx.send(InitStatus::Starting(ffmpeg.describe()));
+
     // 3. Update Check (Non-fatal)
     // yt-dlp 업데이트 확인
+    // 패키지 관리자 등으로 따로 설치했거나 자동 설치를 끈 경우 `-U`로 덮어쓰지 않고 버전만 확인
     let _ = tx.send(InitStatus::Starting(rust_i18n::t!("initialization.ytdlp_update_check").to_string()));
-    match update_ytdlp(&ytdlp_path) {
+    let checked = match ytdlp.source {
+        BinarySource::AppManaged if auto_download => update_ytdlp(&ytdlp.path, proxy),
+        _ => ytdlp_version(&ytdlp.path),
+    };
+    match checked {
         Ok(msg) => {
             let _ = tx.send(InitStatus::Starting(format!("yt-dlp: {}", msg)));
             std::thread::sleep(std::time::Duration::from_millis(1500));
@@ -59,7 +142,7 @@ pub fn init_dependencies(tx: std::sync::mpsc::Sender<InitStatus>) {
 
     // ffmpeg 작동 확인
     let _ = tx.send(InitStatus::Starting(rust_i18n::t!("initialization.ffmpeg_check").to_string()));
-    match check_ffmpeg(&ffmpeg_path) {
+    match check_ffmpeg(&ffmpeg.path) {
         Ok(msg) => {
             let _ = tx.send(InitStatus::Starting(format!("ffmpeg: {}", msg)));
             std::thread::sleep(std::time::Duration::from_millis(1500));
@@ -73,9 +156,9 @@ pub fn init_dependencies(tx: std::sync::mpsc::Sender<InitStatus>) {
     let _ = tx.send(InitStatus::Completed);
 }
 
-fn update_ytdlp(ytdlp_path: &Path) -> ValidatedResult<String> {
+fn update_ytdlp(ytdlp_path: &Path, proxy: Option<&str>) -> ValidatedResult<String> {
     let mut cmd = Command::new(ytdlp_path);
-    cmd.arg("-U");
+    cmd.args(proxy_args(proxy)).arg("-U");
     
     #[cfg(target_os = "windows")]
     {
@@ -84,20 +167,44 @@ fn update_ytdlp(ytdlp_path: &Path) -> ValidatedResult<String> {
     }
 
     let output = cmd.output()
-        .map_err(|e| format!("실행 실패: {}", e))?;
+        .map_err(|e| Error::YtDlpNotFound(format!("{}: {}", ytdlp_path.display(), e)))?;
 
     if !output.status.success() {
          let stderr = String::from_utf8_lossy(&output.stderr);
-         return Err(format!("업데이트 실패: {}", stderr.trim()));
+         return Err(classify_ytdlp_stderr(&stderr));
     }
     
     let stdout = String::from_utf8_lossy(&output.stdout);
     // Find the line containing "up to date" or "Updated"
     let status_line = stdout.lines()
         .find(|l| l.contains("up to date") || l.contains("Updated"))
-        .unwrap_or("업데이트 확인 완료");
-        
-    Ok(status_line.trim().to_string())
+        .map(|l| l.trim().to_string())
+        .unwrap_or_else(|| rust_i18n::t!("initialization.update_checked").to_string());
+
+    Ok(status_line)
+}
+
+/// 따로 설치된 yt-dlp의 버전만 확인
+fn ytdlp_version(ytdlp_path: &Path) -> ValidatedResult<String> {
+    let mut cmd = Command::new(ytdlp_path);
+    cmd.arg("--version");
+
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+    }
+
+    let output = cmd.output()
+        .map_err(|e| Error::YtDlpNotFound(format!("{}: {}", ytdlp_path.display(), e)))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(classify_ytdlp_stderr(&stderr));
+    }
+
+    let version = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    Ok(rust_i18n::t!("initialization.ytdlp_external", version = version).to_string())
 }
 
 fn check_ffmpeg(ffmpeg_path: &Path) -> ValidatedResult<String> {
@@ -111,118 +218,309 @@ fn check_ffmpeg(ffmpeg_path: &Path) -> ValidatedResult<String> {
     }
 
     let output = cmd.output()
-        .map_err(|e| format!("실행 실패: {}", e))?;
+        .map_err(|_| Error::FfmpegMissing)?;
 
     if !output.status.success() {
-         return Err("ffmpeg 실행 중 오류 발생".to_string());
+         return Err(Error::FfmpegMissing);
     }
     
     let stdout = String::from_utf8_lossy(&output.stdout);
-    let version_line = stdout.lines().next().unwrap_or("ffmpeg 감지됨");
-    
+    let Some(version_line) = stdout.lines().next() else {
+        return Ok(rust_i18n::t!("initialization.ffmpeg_detected").to_string());
+    };
+
     // 버전 정보만 간략히 추출 (예: ffmpeg version n6.0 ... -> version n6.0)
     let display_msg = if version_line.len() > 30 {
         &version_line[..30]
     } else {
         version_line
     };
-    
-    Ok(format!("정상 작동 ({})", display_msg))
+
+    Ok(rust_i18n::t!("initialization.ffmpeg_ok", version = display_msg).to_string())
 }
 
-fn get_app_dir() -> PathBuf {
-    dirs::data_local_dir()
-        .unwrap_or_else(|| PathBuf::from("."))
-        .join("rust-yt")
+/// 실행 파일 찾기. 앱 폴더의 파일은 실행해 보고 안 되면 지운 뒤 다시 찾음
+///
+/// 설치 도중 앱이 종료되면 확인하지 못한 파일이 앱 폴더에 남을 수 있으므로,
+/// 이번 실행에서 설치하지 않은 앱 관리 파일은 쓰기 전에 다시 확인한다.
+fn resolve_working(tool: Tool, check: fn(&Path) -> ValidatedResult<String>) -> ValidatedResult<ResolvedBinary> {
+    let found = binaries::resolve(tool)?;
+    if found.source == BinarySource::AppManaged && check(&found.path).is_err() {
+        let _ = fs::remove_file(&found.path);
+        return binaries::resolve(tool);
+    }
+    Ok(found)
 }
 
-fn get_ytdlp_path(app_dir: &Path) -> PathBuf {
-    #[cfg(target_os = "windows")]
-    return app_dir.join("yt-dlp.exe");
-    #[cfg(not(target_os = "windows"))]
-    return app_dir.join("yt-dlp");
+/// 방금 앱 폴더에 설치한 실행 파일
+fn installed(tool: Tool) -> ResolvedBinary {
+    ResolvedBinary { tool, path: binaries::app_managed_path(tool), source: BinarySource::AppManaged }
 }
 
-fn get_ffmpeg_path(app_dir: &Path) -> PathBuf {
-    #[cfg(target_os = "windows")]
-    return app_dir.join("ffmpeg.exe");
-    #[cfg(not(target_os = "windows"))]
-    return app_dir.join("ffmpeg");
+/// 받는 중인 파일 경로 (`<dest>.part`)
+fn part_path(dest: &Path) -> PathBuf {
+    let mut name = dest.as_os_str().to_owned();
+    name.push(".part");
+    PathBuf::from(name)
 }
 
-fn download_file(url: &str, dest: &Path, tx: &std::sync::mpsc::Sender<InitStatus>, filename: &str) -> ValidatedResult<()> {
-    use backoff::{ExponentialBackoff, retry};
-    use std::time::Duration;
+/// `.part`를 받을 때 서버가 준 ETag 또는 Last-Modified (`<dest>.part.validator`)
+///
+/// 이어받을 때 `If-Range`로 보내 서버 파일이 바뀌었으면 206 대신 전체 파일을 받는다.
+fn validator_path(part: &Path) -> PathBuf {
+    let mut name = part.as_os_str().to_owned();
+    name.push(".validator");
+    PathBuf::from(name)
+}
+
+/// 받다 만 파일과 그 검증 값 지우기
+fn discard_part(part: &Path) {
+    let _ = fs::remove_file(part);
+    let _ = fs::remove_file(validator_path(part));
+}
+
+/// 데이터가 이 시간 동안 한 바이트도 오지 않으면 끊긴 것으로 봄
+///
+/// blocking 클라이언트의 timeout은 응답 헤더 대기와 본문 읽기 한 번마다 따로 적용되므로
+/// 전체 다운로드 시간이 아니라 유휴 시간 제한으로 동작한다. 느린 연결이어도 받는 중이면 끊지 않는다.
+const IDLE_TIMEOUT: Duration = Duration::from_secs(30);
+/// 받은 데이터 없이 연속으로 실패할 수 있는 횟수 (조금이라도 받았으면 다시 셈)
+const MAX_STALLED_ATTEMPTS: u32 = 5;
+
+/// `url`을 `<dest>.part`에 받고 받은 내용의 SHA-256(16진수) 반환
+///
+/// 이미 받은 `.part`가 있으면 HTTP Range와 If-Range로 이어받는다. 실패해도 `.part`는 남겨 두어
+/// 재시도나 다음 실행에서 이어받는다.
+fn download_file(url: &str, dest: &Path, tx: &std::sync::mpsc::Sender<InitStatus>, filename: &str, proxy: Option<&str>) -> ValidatedResult<String> {
+    use backoff::ExponentialBackoff;
+    use backoff::backoff::Backoff;
 
     let _ = tx.send(InitStatus::Starting(rust_i18n::t!("initialization.downloading_prep", file = filename).to_string()));
     
-    // 타임아웃 설정된 클라이언트 생성
-    let client = reqwest::blocking::Client::builder()
-        .connect_timeout(Duration::from_secs(30))
-        .timeout(Duration::from_secs(300)) // 5분
-        .build()
-        .map_err(|e| format!("HTTP 클라이언트 생성 실패: {}", e))?;
-
-    // 재시도 설정 (최대 3회, 지수 백오프)
-    let backoff = ExponentialBackoff {
-        max_elapsed_time: Some(Duration::from_secs(60)),
+    // 유휴 시간 제한/프록시 설정된 클라이언트 생성
+    let client = http_client(proxy, IDLE_TIMEOUT)?;
+
+    // 재시도 간격 (지수 백오프). 횟수는 받은 데이터 없이 실패한 횟수로만 제한
+    let mut backoff = ExponentialBackoff {
+        max_elapsed_time: None,
         initial_interval: Duration::from_secs(1),
         max_interval: Duration::from_secs(10),
         ..Default::default()
     };
 
-    let url_owned = url.to_string();
-    let filename_owned = filename.to_string();
-    let tx_clone = tx.clone();
-
-    // 재시도 로직으로 HTTP 요청
-    let response = retry(backoff, || {
-        let _ = tx_clone.send(InitStatus::Starting(rust_i18n::t!("initialization.downloading_attempt", file = filename_owned).to_string()));
-        
-        client.get(&url_owned)
-            .send()
-            .map_err(|e| {
-                let _ = tx_clone.send(InitStatus::Starting(rust_i18n::t!("initialization.downloading_retry", file = filename_owned).to_string()));
-                backoff::Error::transient(e)
-            })
-            .and_then(|resp| {
-                if resp.status().is_success() {
-                    Ok(resp)
-                } else {
-                    Err(backoff::Error::permanent(
-                        reqwest::Error::from(resp.error_for_status().unwrap_err())
-                    ))
+    let part = part_path(dest);
+    let part_len = || fs::metadata(&part).map(|m| m.len()).unwrap_or(0);
+    let mut stalled = 0;
+
+    // 재시도 로직으로 HTTP 요청 (끊기면 받은 데까지 이어받음)
+    for attempt in 0.. {
+        let key = if attempt == 0 { "initialization.downloading_attempt" } else { "initialization.downloading_retry" };
+        let _ = tx.send(InitStatus::Starting(rust_i18n::t!(key, file = filename).to_string()));
+
+        let before = part_len();
+        let err = match download_attempt(&client, url, &part, tx, filename) {
+            Ok(()) => break,
+            Err(backoff::Error::Permanent(e)) => return Err(e),
+            Err(backoff::Error::Transient { err, .. }) => err,
+        };
+
+        // 이번 시도에서 더 받았으면 느려도 진행 중이므로 처음부터 다시 셈
+        if part_len() > before {
+            stalled = 0;
+            backoff.reset();
+        }
+        stalled += 1;
+        if stalled >= MAX_STALLED_ATTEMPTS {
+            return Err(Error::Network(rust_i18n::t!("initialization.download_failed_retry", error = err).to_string()));
+        }
+        std::thread::sleep(backoff.next_backoff().unwrap_or(backoff.max_interval));
+    }
+
+    sha256_file(&part)
+}
+
+/// 한 번의 요청으로 `part`를 끝까지 채우기 (끊기거나 서버가 잠시 실패하면 transient)
+fn download_attempt(
+    client: &reqwest::blocking::Client,
+    url: &str,
+    part: &Path,
+    tx: &std::sync::mpsc::Sender<InitStatus>,
+    filename: &str,
+) -> Result<(), backoff::Error<Error>> {
+    use reqwest::StatusCode;
+    use reqwest::header::{CONTENT_RANGE, ETAG, IF_RANGE, LAST_MODIFIED, RANGE};
+    use std::io::{Read, Write};
+
+    let transient = |e: Error| backoff::Error::transient(e);
+    let validator_file = validator_path(part);
+    let mut offset = fs::metadata(part).map(|m| m.len()).unwrap_or(0);
+    // 어느 파일에서 받았는지 모르는 `.part`는 다른 릴리스일 수 있으므로 처음부터
+    let validator = fs::read_to_string(&validator_file).ok().filter(|v| !v.is_empty());
+    if offset > 0 && validator.is_none() {
+        discard_part(part);
+        offset = 0;
+    }
+
+    let mut request = client.get(url);
+    if offset > 0 && let Some(validator) = &validator {
+        request = request.header(RANGE, format!("bytes={}-", offset)).header(IF_RANGE, validator.as_str());
+    }
+    let mut response = request.send().map_err(|e| transient(e.into()))?;
+
+    let (mut file, mut downloaded, total_size) = match response.status() {
+        StatusCode::PARTIAL_CONTENT if offset > 0 => {
+            let range = response.headers()
+                .get(CONTENT_RANGE)
+                .and_then(|v| v.to_str().ok())
+                .and_then(parse_content_range);
+            match range {
+                Some((start, total)) if start == offset => {
+                    let file = fs::OpenOptions::new().append(true).open(part).map_err(|e| backoff::Error::permanent(e.into()))?;
+                    (file, offset, total)
+                }
+                // 요청한 위치와 다르면 이어 쓸 수 없으므로 처음부터
+                _ => {
+                    discard_part(part);
+                    return Err(transient(Error::Network(format!("unexpected Content-Range from {}", url))));
                 }
-            })
-    }).map_err(|e| rust_i18n::t!("initialization.download_failed_retry", error = e).to_string())?;
+            }
+        }
+        // 받은 부분이 서버 파일보다 큼 (파일이 바뀜) → 처음부터
+        StatusCode::RANGE_NOT_SATISFIABLE => {
+            discard_part(part);
+            return Err(transient(Error::Network(format!("{} changed on the server", url))));
+        }
+        // 처음 받거나 서버 파일이 바뀌어(If-Range 불일치) 전체를 보낸 경우 처음부터 다시 씀
+        status if status.is_success() => {
+            let file = fs::File::create(part).map_err(|e| backoff::Error::permanent(e.into()))?;
+            // If-Range에는 강한 ETag만 쓸 수 있으므로 약한 ETag면 Last-Modified 사용
+            let etag = response.headers().get(ETAG).filter(|v| !v.as_bytes().starts_with(b"W/"));
+            match etag.or_else(|| response.headers().get(LAST_MODIFIED)).and_then(|v| v.to_str().ok()) {
+                Some(validator) => fs::write(&validator_file, validator).map_err(|e| backoff::Error::permanent(e.into()))?,
+                None => {
+                    let _ = fs::remove_file(&validator_file);
+                }
+            }
+            let total = response.content_length();
+            (file, 0, total)
+        }
+        status => {
+            let e = response.error_for_status().err().map_or_else(|| Error::Network(url.to_string()), Error::from);
+            // 서버 오류(5xx)와 요청 과다(429)는 잠시 뒤 다시, 나머지 4xx는 다시 해도 같으므로 바로 실패
+            if status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS {
+                return Err(transient(e));
+            }
+            return Err(backoff::Error::permanent(e));
+        }
+    };
 
-    let total_size = response.content_length().unwrap_or(0);
-    let mut file = fs::File::create(dest).map_err(|e| e.to_string())?;
-    let mut downloaded: u64 = 0;
     let mut buffer = [0; 8192];
+    loop {
+        let bytes_read = response.read(&mut buffer).map_err(|e| transient(Error::Network(e.to_string())))?;
+        if bytes_read == 0 {
+            break;
+        }
+        file.write_all(&buffer[..bytes_read]).map_err(|e| backoff::Error::permanent(e.into()))?;
+        downloaded += bytes_read as u64;
 
+        if let Some(total) = total_size.filter(|&t| t > 0) {
+            let percent = (downloaded as f64 / total as f64) * 100.0;
+            let _ = tx.send(InitStatus::Downloading(percent, filename.to_string()));
+        }
+    }
+    file.flush().map_err(|e| backoff::Error::permanent(e.into()))?;
+
+    if let Some(total) = total_size
+        && downloaded != total
+    {
+        return Err(transient(Error::Network(format!("{}: {} of {} bytes", url, downloaded, total))));
+    }
+    Ok(())
+}
+
+/// "bytes <시작>-<끝>/<전체>" 에서 (시작, 전체) 꺼내기 (전체가 "*"이면 None)
+fn parse_content_range(value: &str) -> Option<(u64, Option<u64>)> {
+    let (range, total) = value.strip_prefix("bytes ")?.split_once('/')?;
+    let start = range.split_once('-')?.0.parse().ok()?;
+    Some((start, total.parse().ok()))
+}
+
+fn sha256_file(path: &Path) -> ValidatedResult<String> {
     use std::io::Read;
-    use std::io::Write;
 
-    let mut response = response;
+    let mut file = fs::File::open(path)?;
+    let mut hasher = ring::digest::Context::new(&ring::digest::SHA256);
+    let mut buffer = [0; 64 * 1024];
     loop {
-        let bytes_read = response.read(&mut buffer).map_err(|e| e.to_string())?;
+        let bytes_read = file.read(&mut buffer)?;
         if bytes_read == 0 {
             break;
         }
-        file.write_all(&buffer[..bytes_read]).map_err(|e| e.to_string())?;
-        downloaded += bytes_read as u64;
+        hasher.update(&buffer[..bytes_read]);
+    }
+    Ok(hasher.finish().as_ref().iter().map(|b| format!("{:02x}", b)).collect())
+}
 
-        if total_size > 0 {
-            let percent = (downloaded as f64 / total_size as f64) * 100.0;
-            let _ = tx.send(InitStatus::Downloading(percent, filename.to_string()));
+/// 공개된 SHA-256 목록을 먼저 받고, 파일을 받은 뒤 해시가 다르면 지우고 실패
+///
+/// 목록에 파일이 없을 때도 설치하지 않는다. `sums_url`이 없으면 확인 없이 설치하고 그 사실을 알린다.
+/// `dest`에는 다 받고 확인까지 끝난 파일만 이름을 바꿔 옮기므로 받다 만 파일이 설치되지 않는다.
+pub fn download_verified(artifact: &Artifact, dest: &Path, tx: &std::sync::mpsc::Sender<InitStatus>, label: &str, proxy: Option<&str>) -> ValidatedResult<()> {
+    download_validated(artifact, dest, tx, label, proxy, |_| Ok(()))
+}
+
+/// `download_verified`와 같지만 옮기기 전에 `.part`를 `validate`로 검사 (실행해 보기 등)
+///
+/// 검사에 실패하면 `.part`만 지우고 기존 `dest`는 그대로 둔다.
+pub fn download_validated(
+    artifact: &Artifact,
+    dest: &Path,
+    tx: &std::sync::mpsc::Sender<InitStatus>,
+    label: &str,
+    proxy: Option<&str>,
+    validate: impl FnOnce(&Path) -> ValidatedResult<()>,
+) -> ValidatedResult<()> {
+    let file_name = artifact.file_name();
+    let expected = match &artifact.sums_url {
+        Some(sums_url) => {
+            let client = http_client(proxy, std::time::Duration::from_secs(60))?;
+            let sums = client.get(sums_url).send()?.error_for_status()?.text()?;
+            let expected = find_checksum(&sums, file_name)
+                .ok_or_else(|| Error::ChecksumMismatch(rust_i18n::t!("initialization.checksum_not_listed", file = file_name).to_string()))?;
+            Some(expected)
         }
+        None => None,
+    };
+
+    let part = part_path(dest);
+    let resumed = part.exists();
+    let mut actual = download_file(&artifact.url, dest, tx, label, proxy)?;
+    // 이전 실행에서 남은 `.part`는 다른 릴리스였을 수 있으므로 한 번은 처음부터 다시 받음
+    if resumed && expected.as_ref().is_some_and(|expected| *expected != actual) {
+        discard_part(&part);
+        actual = download_file(&artifact.url, dest, tx, label, proxy)?;
+    }
+
+    let _ = tx.send(InitStatus::Starting(rust_i18n::t!("initialization.verifying", file = label).to_string()));
+    if let Some(expected) = &expected
+        && *expected != actual
+    {
+        discard_part(&part);
+        return Err(Error::ChecksumMismatch(rust_i18n::t!("initialization.checksum_differs", file = file_name, expected = expected, actual = actual).to_string()));
+    }
+
+    if let Err(e) = validate(&part) {
+        discard_part(&part);
+        return Err(validation_error(label, e));
     }
 
+    fs::rename(&part, dest)?;
+    let _ = fs::remove_file(validator_path(&part));
+    let _ = tx.send(InitStatus::Verified(label.to_string(), expected));
     Ok(())
 }
 
-fn download_ytdlp(app_dir: &Path, tx: &std::sync::mpsc::Sender<InitStatus>) -> ValidatedResult<()> {
+fn download_ytdlp(app_dir: &Path, tx: &std::sync::mpsc::Sender<InitStatus>, proxy: Option<&str>) -> ValidatedResult<()> {
     #[cfg(target_os = "linux")]
     let url = "https://github.com/yt-dlp/yt-dlp/releases/latest/download/yt-dlp";
     #[cfg(target_os = "macos")]
@@ -230,36 +528,48 @@ fn download_ytdlp(app_dir: &Path, tx: &std::sync::mpsc::Sender<InitStatus>) -> V
     #[cfg(target_os = "windows")]
     let url = "https://github.com/yt-dlp/yt-dlp/releases/latest/download/yt-dlp.exe";
 
-    let dest = get_ytdlp_path(app_dir);
-    download_file(url, &dest, tx, "yt-dlp")?;
-
-    #[cfg(not(target_os = "windows"))]
-    {
-        use std::os::unix::fs::PermissionsExt;
-        let mut perms = fs::metadata(&dest).unwrap().permissions();
-        perms.set_mode(0o755);
-        fs::set_permissions(&dest, perms).unwrap();
-    }
+    let artifact = Artifact {
+        url: url.to_string(),
+        sums_url: Some("https://github.com/yt-dlp/yt-dlp/releases/latest/download/SHA2-256SUMS".to_string()),
+    };
+    let dest = app_dir.join(Tool::YtDlp.file_name());
+    // 실행되지 않는 파일은 설치하지 않음 (기존 yt-dlp는 그대로 남김)
+    download_validated(&artifact, &dest, tx, "yt-dlp", proxy, |part| {
+        #[cfg(not(target_os = "windows"))]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mut perms = fs::metadata(part)?.permissions();
+            perms.set_mode(0o755);
+            fs::set_permissions(part, perms)?;
+        }
+        ytdlp_version(part).map(|_| ())
+    })
+}
 
-    Ok(())
+fn validation_error(file: &str, e: Error) -> Error {
+    Error::Io(rust_i18n::t!("initialization.validation_failed", file = file, error = e).to_string())
 }
 
-fn download_ffmpeg(app_dir: &Path, tx: &std::sync::mpsc::Sender<InitStatus>) -> ValidatedResult<()> {
+fn download_ffmpeg(app_dir: &Path, tx: &std::sync::mpsc::Sender<InitStatus>, proxy: Option<&str>) -> ValidatedResult<()> {
     let _ = tx.send(InitStatus::Starting(rust_i18n::t!("initialization.ffmpeg_check").to_string()));
 
+    // BtbN 빌드는 같은 릴리스에 checksums.sha256을, evermeet.cx(macOS)는 파일마다 .sha256을 올린다
     #[cfg(target_os = "linux")]
-    let (url, archive_name) = (
+    let (url, sums_url, archive_name) = (
         "https://github.com/BtbN/FFmpeg-Builds/releases/download/latest/ffmpeg-master-latest-linux64-gpl.tar.xz",
+        "https://github.com/BtbN/FFmpeg-Builds/releases/download/latest/checksums.sha256",
         "ffmpeg.tar.xz"
     );
      #[cfg(target_os = "macos")]
-    let (url, archive_name) = (
-        "https://evermeet.cx/ffmpeg/ffmpeg-6.0.zip", // Note: macOS builds vary, using a common one
+    let (url, sums_url, archive_name) = (
+        "https://evermeet.cx/ffmpeg/ffmpeg-6.0.zip",
+        "https://evermeet.cx/ffmpeg/ffmpeg-6.0.zip.sha256",
         "ffmpeg.zip"
     );
     #[cfg(target_os = "windows")]
-     let (url, archive_name) = (
+     let (url, sums_url, archive_name) = (
         "https://github.com/BtbN/FFmpeg-Builds/releases/download/latest/ffmpeg-master-latest-win64-gpl.zip",
+        "https://github.com/BtbN/FFmpeg-Builds/releases/download/latest/checksums.sha256",
         "ffmpeg.zip"
     );
 
@@ -272,64 +582,60 @@ fn download_ffmpeg(app_dir: &Path, tx: &std::sync::mpsc::Sender<InitStatus>) ->
     // Let's assume user has it or we use a static build.
     // Using BtbN for Linux/Windows is reliable. 
     
-    download_file(url, &archive_path, tx, "ffmpeg archive")?;
+    let artifact = Artifact { url: url.to_string(), sums_url: Some(sums_url.to_string()) };
+    download_verified(&artifact, &archive_path, tx, "ffmpeg archive", proxy)?;
 
     let _ = tx.send(InitStatus::Extracting(rust_i18n::t!("initialization.extracting", file = "ffmpeg").to_string()));
 
-    if archive_name.ends_with(".zip") {
-        let file = fs::File::open(&archive_path).map_err(|e| e.to_string())?;
-        let mut archive = ZipArchive::new(file).map_err(|e| e.to_string())?;
-
-        for i in 0..archive.len() {
-            let mut file = archive.by_index(i).unwrap();
-            let name = file.name().to_string();
-
-            if name.ends_with("ffmpeg") || name.ends_with("ffmpeg.exe") {
-                 let dest_path = get_ffmpeg_path(app_dir);
-                 let mut outfile = fs::File::create(&dest_path).map_err(|e| e.to_string())?;
-                 copy(&mut file, &mut outfile).map_err(|e| e.to_string())?;
-                 
-                 #[cfg(not(target_os = "windows"))]
-                {
-                    use std::os::unix::fs::PermissionsExt;
-                    let mut perms = fs::metadata(&dest_path).unwrap().permissions();
-                    perms.set_mode(0o755);
-                    fs::set_permissions(&dest_path, perms).unwrap();
-                }
-            }
-        }
-    } else if archive_name.ends_with(".tar.xz") {
-         // tar.xz extraction requires xz2 crate or command line
-         // Simpler to just use Command for tar if available (Linux usually has tar)
-         let status = Command::new("tar")
-            .arg("-xf")
-            .arg(&archive_path)
-            .arg("-C")
-            .arg(app_dir)
-            .status()
-            .map_err(|e| format!("tar 실행 실패: {}", e))?;
-            
-        if !status.success() {
-             return Err("tar 압축 해제 실패".to_string());
-        }
-        
-        // Find ffmpeg binary in the extracted folder and move it
-        // The structure is usually ffmpeg-master-latest-linux64-gpl/bin/ffmpeg
-        for entry in fs::read_dir(app_dir).unwrap() {
-            let entry = entry.unwrap();
-            if entry.file_type().unwrap().is_dir() && entry.file_name().to_string_lossy().contains("ffmpeg") {
-                 let bin_path = entry.path().join("bin").join("ffmpeg");
-                 if bin_path.exists() {
-                     fs::rename(bin_path, get_ffmpeg_path(app_dir)).unwrap();
-                 }
-            }
-        }
+    // 빌드 폴더 전체 대신 ffmpeg/ffprobe 실행 파일만 앱 폴더에 꺼냄
+    let names = [Tool::Ffmpeg.file_name(), format!("ffprobe{}", std::env::consts::EXE_SUFFIX)];
+    let extracted = if archive_name.ends_with(".zip") {
+        archive::extract_zip(&archive_path, &names, app_dir)
+    } else {
+        archive::extract_tar_xz(&archive_path, &names, app_dir)
+    };
+    let _ = fs::remove_file(&archive_path);
+
+    let extracted = extracted?;
+    if !extracted.contains(&names[0]) {
+        return Err(Error::Archive(format!("{} not found in {}", names[0], archive_name)));
     }
 
-    // Cleanup
-    let _ = fs::remove_file(archive_path);
-    
+    // 실행되지 않으면 꺼낸 파일을 모두 지움
+    if let Err(e) = check_ffmpeg(&app_dir.join(&names[0])) {
+        for name in &extracted {
+            let _ = fs::remove_file(app_dir.join(name));
+        }
+        return Err(validation_error("ffmpeg", e));
+    }
     Ok(())
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_checksum_for_file() {
+        let hash = "a".repeat(64);
+        let sums = format!("{}  yt-dlp.exe\n{}  yt-dlp\n{} *ffmpeg.zip\nnot a line\n", "b".repeat(64), hash, "C".repeat(64));
+        assert_eq!(find_checksum(&sums, "yt-dlp"), Some(hash));
+        assert_eq!(find_checksum(&sums, "ffmpeg.zip"), Some("c".repeat(64)));
+        assert_eq!(find_checksum(&sums, "yt-dlp_macos"), None);
+        assert_eq!(find_checksum("xyz  yt-dlp", "yt-dlp"), None);
+        assert_eq!(find_checksum(&format!("{}\n", "D".repeat(64)), "ffmpeg-6.0.zip"), Some("d".repeat(64)));
+    }
 
+    #[test]
+    fn parses_content_range() {
+        assert_eq!(parse_content_range("bytes 8-17/18"), Some((8, Some(18))));
+        assert_eq!(parse_content_range("bytes 0-99/*"), Some((0, None)));
+        assert_eq!(parse_content_range("items 0-1/2"), None);
+    }
+
+    #[test]
+    fn part_file_sits_next_to_destination() {
+        assert_eq!(part_path(Path::new("/tmp/rust-yt/yt-dlp.exe")), PathBuf::from("/tmp/rust-yt/yt-dlp.exe.part"));
+        assert_eq!(validator_path(Path::new("/tmp/rust-yt/yt-dlp.part")), PathBuf::from("/tmp/rust-yt/yt-dlp.part.validator"));
+    }
+}
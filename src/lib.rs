@@ -2,5 +2,15 @@ pub mod downloader;
 pub mod playlist;
 pub mod initializer;
 pub mod config;
+pub mod queue;
+pub mod history;
+pub mod error;
+pub mod template;
+pub mod auth;
+pub mod network;
+pub mod binaries;
+pub mod archive;
+
+pub use error::Error;
 
 rust_i18n::i18n!("locales");
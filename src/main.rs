@@ -1,12 +1,23 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod thumbnails;
+
 use eframe::egui;
-use rust_yt::playlist::{fetch_playlist_info, PlaylistInfo, VideoEntry};
-use rust_yt::downloader::{download_video, DownloadConfig, DownloadFormat, DownloadStatus};
+use rust_yt::playlist::{fetch_formats, fetch_playlist_info, FormatInfo, PlaylistInfo, VideoEntry};
+use rust_yt::downloader::{DownloadConfig, DownloadFormat, SponsorBlockOptions, SponsorCategory, SubtitleFormat, SubtitleOptions, TimeRange, VideoCodec, AUDIO_BITRATES, AUDIO_VBR_LEVELS, BIT_DEPTHS, MAX_SLEEP_SECS, RATE_LIMITS, SAMPLE_RATES, VIDEO_FPS, VIDEO_HEIGHTS};
+use rust_yt::queue::{run_queue, ItemState, QueueEvent, QueueHandle, QueueItem, QueueProgress, SavedQueue, SavedQueueItem};
+use rust_yt::auth::{AuthOptions, BROWSERS};
+use rust_yt::binaries::Tool;
 use rust_yt::config::AppConfig;
+use rust_yt::template::{expand_template, playlist_template, TemplateVars, PLACEHOLDERS};
+use rust_yt::Error;
+use std::collections::HashMap;
+use std::sync::Arc;
 use std::sync::mpsc::{channel, Receiver, Sender};
 use std::thread;
 use std::path::PathBuf;
+use std::time::Duration;
+use thumbnails::ThumbnailLoader;
 
 rust_i18n::i18n!("locales");
 
@@ -28,7 +39,10 @@ fn main() -> eframe::Result<()> {
         Box::new(|cc| {
             setup_custom_fonts(&cc.egui_ctx);
             egui_extras::install_image_loaders(&cc.egui_ctx); // [NEW] 이미지 로더 설치
-            Ok(Box::new(MyApp::default()))
+            let app = MyApp::default();
+            // 나중에 추가한 로더가 먼저 쓰이므로 http 썸네일은 프록시 로더가 받음
+            cc.egui_ctx.add_bytes_loader(app.thumbnails.clone());
+            Ok(Box::new(app))
         }),
     )
 }
@@ -96,6 +110,7 @@ fn setup_custom_fonts(ctx: &egui::Context) {
 #[derive(Debug)]
 enum AppState {
     Initializing, // [NEW] 초기화 (다운로드 등)
+    ToolsMissing(Vec<Tool>), // 자동 설치 없이는 yt-dlp/ffmpeg를 준비할 수 없음
     SetPath, // [NEW] 초기 경로 설정
     Input,
     Analyzing,
@@ -108,20 +123,57 @@ struct MyApp {
     download_dir: PathBuf, // 저장 경로
     url: String,
     format: DownloadFormat,
+    audio_quality: String,     // MP3/M4A 비트레이트 또는 VBR 레벨
+    sample_rate: Option<u32>,  // WAV/FLAC
+    bit_depth: Option<u8>,     // WAV/FLAC
+    max_height: Option<u32>,   // MP4/WEBM
+    max_fps: Option<u32>,      // MP4/WEBM
+    video_codec: VideoCodec,   // MP4/WEBM
+    output_template: String,   // 파일명 템플릿
+    playlist_subfolder: bool,  // 플레이리스트 폴더 + 트랙 번호
+    subtitles: SubtitleOptions,
+    subtitle_langs_input: String, // 자막 언어 입력칸 ("en, ko")
+    sponsorblock: SponsorBlockOptions,
+    auth: AuthOptions,
+    auth_prompt: bool,             // 로그인 필요 오류 후 인증 설정 펼치기
+    proxy: Option<String>,         // 적용된 프록시
+    proxy_input: String,
+    proxy_testing: bool,
+    proxy_status: Option<Result<String, String>>, // 연결 테스트/적용 결과
+    thumbnails: Arc<ThumbnailLoader>,
+    rate_limit: Option<u32>,           // KiB/s
+    sleep_interval: Option<(u32, u32)>, // 다운로드 사이 대기 (초)
+    ytdlp_path: Option<PathBuf>,        // 직접 지정한 실행 파일 (None이면 자동)
+    ffmpeg_path: Option<PathBuf>,
+    use_system_tools: bool,             // 내려받기/업데이트 안 함
     state: AppState,
     playlist_info: Option<PlaylistInfo>,
+    analysis_id: u64, // 분석할 때마다 증가, 이전 분석의 늦은 결과는 버림
     error_msg: Option<String>,
+
+    // 단일 영상 스트림 선택
+    formats: Vec<FormatInfo>,
+    formats_loading: bool,
+    formats_error: Option<String>,
+    video_format_id: Option<String>, // None이면 화질 설정대로 자동 선택
+    audio_format_id: Option<String>,
+    split_chapters: bool,            // 챕터마다 파일 하나씩
+    clip_start: String,              // 구간 시작 입력 ("1:02:03")
+    clip_end: String,                // 구간 끝 입력
+    precise_cuts: bool,
     
     // 다운로드 관련
     download_queue: Vec<VideoEntry>,
-    current_download_idx: usize,
-    progress: f64,
+    queue_configs: Vec<DownloadConfig>,
+    queue_states: Vec<ItemState>,
+    queue_progress: QueueProgress,
+    max_concurrent_downloads: usize,
     progress_text: String,
     
     // 비동기 통신
     tx_ui: Sender<UiMessage>,
     rx_ui: Receiver<UiMessage>,
-    stop_tx: Option<Sender<()>>,
+    queue_handle: Option<QueueHandle>,
     
     // 초기화 상태 표시용
     init_status: String,
@@ -129,12 +181,36 @@ struct MyApp {
     
     // 설정 저장 시 경로 설정 건너뛰기
     skip_set_path: bool,
+
+    // 이전 실행에서 끝나지 않은 큐 (이어받기 확인용)
+    pending_resume: Option<SavedQueue>,
+}
+
+/// 의존성 초기화를 별도 스레드에서 실행하고 상태를 UI로 전달
+fn spawn_init(tx: Sender<UiMessage>, proxy: Option<String>, auto_download: bool) {
+    thread::spawn(move || {
+        let (init_tx, init_rx) = channel();
+
+        // 실제 초기화 작업 수행 (별도 스레드)
+        thread::spawn(move || {
+            rust_yt::initializer::init_dependencies(init_tx, proxy, auto_download);
+        });
+
+        // UI로 상태 전달
+        while let Ok(status) = init_rx.recv() {
+            if tx.send(UiMessage::InitStatus(status)).is_err() {
+                break;
+            }
+        }
+    });
 }
 
 enum UiMessage {
     InitStatus(rust_yt::initializer::InitStatus),
-    AnalysisDone(Result<PlaylistInfo, String>),
-    DownloadProgress(DownloadStatus),
+    AnalysisDone(u64, Result<PlaylistInfo, Error>),
+    FormatsLoaded(u64, Result<Vec<FormatInfo>, Error>),
+    ProxyTested(Result<Duration, Error>),
+    Queue(QueueEvent),
 }
 
     impl Default for MyApp {
@@ -163,55 +239,62 @@ enum UiMessage {
         };
 
         // [초기화 스레드 시작]
-        let tx_clone = tx.clone();
-        let has_saved_path = saved_config.download_dir.is_some();
-        thread::spawn(move || {
-            let (init_tx, init_rx) = channel();
-            
-            // 실제 초기화 작업 수행 (별도 스레드)
-            thread::spawn(move || {
-                rust_yt::initializer::init_dependencies(init_tx);
-            });
-
-            // UI로 상태 전달
-            while let Ok(status) = init_rx.recv() {
-                // 저장된 경로가 있으면 Completed 시 Input으로 직행
-                let modified_status = if has_saved_path {
-                    if let rust_yt::initializer::InitStatus::Completed = &status {
-                        // Completed 상태는 그대로 전달 (이미 initial_state가 Input임)
-                    }
-                    status
-                } else {
-                    status
-                };
-                
-                if tx_clone.send(UiMessage::InitStatus(modified_status)).is_err() {
-                    break;
-                }
-            }
-        });
+        rust_yt::binaries::set_configured_paths(saved_config.ytdlp_path.clone(), saved_config.ffmpeg_path.clone());
+        spawn_init(tx.clone(), saved_config.proxy.clone(), !saved_config.use_system_tools);
 
         Self {
             download_dir: initial_dir,
             url: String::new(),
             format: initial_format,
-            state: if saved_config.download_dir.is_some() { 
-                AppState::Initializing // 초기화 후 Input으로
-            } else {
-                AppState::Initializing
-            },
+            audio_quality: saved_config.audio_quality.clone(),
+            sample_rate: saved_config.sample_rate,
+            bit_depth: saved_config.bit_depth,
+            max_height: saved_config.max_height,
+            max_fps: saved_config.max_fps,
+            video_codec: saved_config.video_codec,
+            output_template: saved_config.output_template.clone(),
+            playlist_subfolder: saved_config.playlist_subfolder,
+            subtitle_langs_input: saved_config.subtitles.languages.join(", "),
+            subtitles: saved_config.subtitles.clone(),
+            sponsorblock: saved_config.sponsorblock.clone(),
+            auth: saved_config.auth.clone(),
+            auth_prompt: false,
+            proxy_input: saved_config.proxy.clone().unwrap_or_default(),
+            proxy: saved_config.proxy.clone(),
+            proxy_testing: false,
+            proxy_status: None,
+            thumbnails: Arc::new(ThumbnailLoader::new(saved_config.proxy.clone())),
+            rate_limit: saved_config.rate_limit,
+            sleep_interval: saved_config.sleep_interval,
+            ytdlp_path: saved_config.ytdlp_path.clone(),
+            ffmpeg_path: saved_config.ffmpeg_path.clone(),
+            use_system_tools: saved_config.use_system_tools,
+            state: AppState::Initializing, // 초기화 후 SetPath 또는 Input으로
             playlist_info: None,
+            analysis_id: 0,
             error_msg: None,
+            formats: Vec::new(),
+            formats_loading: false,
+            formats_error: None,
+            video_format_id: None,
+            audio_format_id: None,
+            split_chapters: false,
+            clip_start: String::new(),
+            clip_end: String::new(),
+            precise_cuts: false,
             download_queue: Vec::new(),
-            current_download_idx: 0,
-            progress: 0.0,
+            queue_configs: Vec::new(),
+            queue_states: Vec::new(),
+            queue_progress: QueueProgress::default(),
+            max_concurrent_downloads: saved_config.max_concurrent_downloads,
             progress_text: String::new(),
             tx_ui: tx,
             rx_ui: rx,
-            stop_tx: None,
+            queue_handle: None,
             init_status: rust_i18n::t!("initialization.preparing").to_string(),
             init_progress: 0.0,
             skip_set_path: saved_config.download_dir.is_some(),
+            pending_resume: None,
         }
     }
 }
@@ -219,100 +302,354 @@ enum UiMessage {
 impl MyApp {
     fn start_analysis(&mut self) {
         let url = self.url.clone();
+        let auth = self.auth.clone();
+        let proxy = self.proxy.clone();
         let tx = self.tx_ui.clone();
+        self.analysis_id += 1;
+        let analysis_id = self.analysis_id;
         
         self.state = AppState::Analyzing;
         self.error_msg = None;
+        self.formats.clear();
+        self.formats_error = None;
+        self.video_format_id = None;
+        self.audio_format_id = None;
+        self.split_chapters = false;
+        self.clip_start.clear();
+        self.clip_end.clear();
         
         thread::spawn(move || {
-            let result = fetch_playlist_info(&url);
-            let _ = tx.send(UiMessage::AnalysisDone(result));
+            let result = fetch_playlist_info(&url, &auth, proxy.as_deref());
+            // 단일 영상이면 이어서 스트림 목록 조회
+            let single_url = result.as_ref().ok()
+                .filter(|info| !info.is_playlist)
+                .and_then(|info| info.entries.first())
+                .map(|entry| entry.url.clone());
+            let _ = tx.send(UiMessage::AnalysisDone(analysis_id, result));
+
+            if let Some(url) = single_url {
+                let _ = tx.send(UiMessage::FormatsLoaded(analysis_id, fetch_formats(&url, &auth, proxy.as_deref())));
+            }
         });
     }
 
+    /// 실제로 쓸 템플릿 (플레이리스트 폴더 옵션 반영)
+    fn effective_template(&self, info: &PlaylistInfo) -> String {
+        if info.is_playlist && self.playlist_subfolder {
+            playlist_template(&self.output_template, info.last_index())
+        } else {
+            self.output_template.clone()
+        }
+    }
+
+    /// 첫 번째 선택 영상(없으면 예시 값)으로 만든 저장 경로 미리보기
+    fn template_preview(&self) -> Result<PathBuf, Error> {
+        let ext = AppConfig::format_to_string(&self.format);
+        let vars = self.playlist_info.as_ref()
+            .and_then(|info| {
+                info.entries.iter()
+                    .find(|e| e.selected)
+                    .map(|e| TemplateVars::new(e, info.is_playlist.then_some(info), &self.format))
+            })
+            .unwrap_or_else(|| TemplateVars {
+                title: "Video Title".to_string(),
+                id: "dQw4w9WgXcQ".to_string(),
+                uploader: Some("Uploader".to_string()),
+                playlist: Some("Playlist".to_string()),
+                index: Some(1),
+                format: ext.clone(),
+            });
+        let template = match &self.playlist_info {
+            Some(info) => self.effective_template(info),
+            // 분석 전에는 예시 값이 플레이리스트 항목이므로 옵션만 보고 판단
+            None if self.playlist_subfolder => playlist_template(&self.output_template, 1),
+            None => self.output_template.clone(),
+        };
+        let relative = expand_template(&template, &vars, &ext)?;
+        // 미리보기에서는 yt-dlp용 `%%` 이스케이프를 되돌림
+        Ok(self.download_dir.join(relative.to_string_lossy().replace("%%", "%")))
+    }
+
+    /// 직접 고른 스트림으로 만든 yt-dlp 형식 선택자 (단일 영상 전용)
+    fn selected_format_id(&self) -> Option<String> {
+        let video = self.video_format_id.as_ref().filter(|_| !self.format.is_audio());
+        match (video, &self.audio_format_id) {
+            (Some(video), Some(audio)) => Some(format!("{}+{}", video, audio)),
+            (Some(video), None) => {
+                // 소리가 포함된 스트림이면 그대로, 아니면 최고 음질과 병합
+                let muxed = self.formats.iter().any(|f| &f.format_id == video && f.has_audio());
+                Some(if muxed { video.clone() } else { format!("{}+bestaudio", video) })
+            }
+            (None, Some(audio)) if self.format.is_audio() => Some(audio.clone()),
+            (None, Some(audio)) => Some(format!("bestvideo+{}", audio)),
+            (None, None) => None,
+        }
+    }
+
     fn start_download(&mut self) -> Result<(), String> {
         let info = self.playlist_info.as_ref().ok_or(rust_i18n::t!("main.need_analysis").to_string())?;
         
         // 선택된 영상만 필터링
-        self.download_queue = info.entries.iter()
+        let selected: Vec<&VideoEntry> = info.entries.iter()
             .filter(|e| e.selected)
-            .cloned()
             .collect();
             
-        if self.download_queue.is_empty() {
+        if selected.is_empty() {
             return Err(rust_i18n::t!("main.no_selection").to_string());
         }
 
-        self.current_download_idx = 0;
+        let format_id = if info.is_playlist { None } else { self.selected_format_id() };
+
+        // 챕터 나누기 (단일 영상만 챕터 목록이 있음)
+        let split_chapters = self.split_chapters && !info.is_playlist;
+        let chapters = selected.first().map(|video| video.chapters.as_slice()).unwrap_or_default();
+        if split_chapters && !chapters.iter().any(|c| c.selected) {
+            return Err(rust_i18n::t!("main.no_chapter_selection").to_string());
+        }
+        let skipped_chapters: Vec<(f64, f64)> = chapters.iter()
+            .filter(|c| split_chapters && !c.selected)
+            .map(|c| (c.start_time, c.end_time))
+            .collect();
+
+        // 일부 구간만 받기 (단일 영상만)
+        let time_range = match (info.is_playlist, selected.first()) {
+            (false, Some(video)) => TimeRange::parse(&self.clip_start, &self.clip_end, video.duration)
+                .map_err(|e| e.to_string())?,
+            _ => None,
+        };
+
+        // 파일명 템플릿 확장 (하나라도 잘못되면 시작하지 않음)
+        let template = self.effective_template(info);
+        let output_names = selected.iter()
+            .map(|video| {
+                let vars = TemplateVars::new(video, info.is_playlist.then_some(info), &self.format);
+                expand_template(&template, &vars, "%(ext)s")
+            })
+            .collect::<Result<Vec<_>, Error>>()
+            .map_err(|e| e.to_string())?;
+
+        self.download_queue = selected.into_iter().cloned().collect();
+        self.queue_configs = self.download_queue.iter()
+            .zip(output_names)
+            .map(|(video, output_name)| DownloadConfig {
+                url: video.url.clone(),
+                format: self.format.clone(),
+                audio_quality: self.audio_quality.clone(),
+                sample_rate: self.sample_rate,
+                bit_depth: self.bit_depth,
+                max_height: self.max_height,
+                max_fps: self.max_fps,
+                video_codec: self.video_codec,
+                format_id: format_id.clone(),
+                output_dir: self.download_dir.clone(), // [NEW] 선택된 경로 사용
+                output_name: Some(output_name),
+                subtitles: self.subtitles.clone(),
+                split_chapters,
+                skipped_chapters: skipped_chapters.clone(),
+                time_range,
+                precise_cuts: self.precise_cuts,
+                sponsorblock: self.sponsorblock.clone(),
+                auth: self.auth.clone(),
+                proxy: self.proxy.clone(),
+                rate_limit: self.rate_limit,
+                sleep_interval: self.sleep_interval,
+            })
+            .collect();
+
         self.state = AppState::Downloading;
-        self.download_next();
+        self.start_queue();
         Ok(())
     }
+
+    /// 이전 실행에서 끝나지 않은 항목 이어받기
+    fn resume_saved_queue(&mut self, saved: SavedQueue) {
+        let (videos, configs): (Vec<_>, Vec<_>) = saved.unfinished()
+            .map(|item| (item.video.clone(), item.config.clone()))
+            .unzip();
+
+        self.playlist_info = Some(PlaylistInfo {
+            title: saved.title,
+            entries: videos.clone(),
+            is_playlist: saved.is_playlist,
+        });
+        self.download_queue = videos;
+        self.queue_configs = configs;
+        self.state = AppState::Downloading;
+        self.start_queue();
+    }
     
     fn stop_download(&mut self) {
-        if let Some(tx) = &self.stop_tx {
-            let _ = tx.send(());
+        if let Some(handle) = &self.queue_handle {
+            handle.stop();
         }
-        // stop_tx는 즉시 해제하지 않고, 스레드가 종료되어 Failed/Stopped 메시지를 보낼 때까지 기다리거나
-        // UI 반응성을 위해 즉시 상태 변경
+        // 큐가 Finished 이벤트를 보낼 때까지 기다리되, UI 반응성을 위해 즉시 문구 변경
         self.progress_text = rust_i18n::t!("main.download_stopped").to_string();
     }
 
-    fn download_next(&mut self) {
-        if self.current_download_idx >= self.download_queue.len() {
-            self.state = AppState::Finished;
-            self.progress_text = rust_i18n::t!("main.all_completed").to_string();
-            self.progress = 1.0;
-            self.stop_tx = None;
+    fn start_queue(&mut self) {
+        let items = self.download_queue.iter()
+            .zip(&self.queue_configs)
+            .map(|(video, config)| QueueItem {
+                id: video.id.clone(),
+                title: video.title.clone(),
+                config: config.clone(),
+            })
+            .collect();
+
+        // UI 초기화
+        self.queue_states = vec![ItemState::Pending; self.download_queue.len()];
+        self.queue_progress = QueueProgress::from_states(&self.queue_states);
+        self.progress_text.clear();
+        self.save_queue();
+
+        let (queue_tx, queue_rx) = channel();
+        self.queue_handle = Some(run_queue(items, self.max_concurrent_downloads, queue_tx));
+
+        // 중계 루프 (QueueEvent -> UiMessage)
+        let tx = self.tx_ui.clone();
+        thread::spawn(move || {
+            while let Ok(event) = queue_rx.recv() {
+                if tx.send(UiMessage::Queue(event)).is_err() {
+                    break; // UI가 닫히면 종료
+                }
+            }
+        });
+    }
+
+    /// 완료된 큐 항목을 목록에 "받음"으로 표시
+    fn mark_downloaded(&mut self, idx: usize) {
+        let (Some(video), Some(config), Some(info)) = (self.download_queue.get(idx), self.queue_configs.get(idx), &mut self.playlist_info) else {
             return;
+        };
+        for entry in info.entries.iter_mut().filter(|e| e.id == video.id) {
+            if !entry.downloaded_formats.contains(&config.format) {
+                entry.downloaded_formats.push(config.format.clone());
+            }
         }
+    }
 
-        let video = self.download_queue[self.current_download_idx].clone();
-        let tx = self.tx_ui.clone();
-        
-        let config = DownloadConfig {
-            url: video.url.clone(),
-            format: self.format.clone(),
-            audio_quality: "320K".to_string(),
-            output_dir: self.download_dir.clone(), // [NEW] 선택된 경로 사용
+    /// 실패한 항목 수와 오류/stderr 전체 텍스트
+    fn failure_details(&self) -> Option<(usize, String)> {
+        let failures: Vec<String> = self.download_queue.iter()
+            .zip(&self.queue_states)
+            .filter_map(|(video, state)| match state {
+                ItemState::Failed(e, stderr) => Some(format!("[{}] {}\n{}\n{}", video.id, video.title, e, stderr.join("\n"))),
+                _ => None,
+            })
+            .collect();
+
+        if failures.is_empty() {
+            None
+        } else {
+            Some((failures.len(), failures.join("\n\n")))
+        }
+    }
+
+    /// 재시작 후 이어받을 수 있도록 현재 큐 저장
+    fn save_queue(&self) {
+        let Some(info) = &self.playlist_info else {
+            return;
+        };
+        let saved = SavedQueue {
+            title: info.title.clone(),
+            is_playlist: info.is_playlist,
+            items: self.download_queue.iter()
+                .zip(&self.queue_configs)
+                .zip(&self.queue_states)
+                .map(|((video, config), state)| SavedQueueItem {
+                    video: video.clone(),
+                    config: config.clone(),
+                    state: state.clone(),
+                })
+                .collect(),
         };
+        let _ = saved.save();
+    }
 
-        // UI 초기화
-        self.progress = 0.0;
-        self.progress_text = rust_i18n::t!("main.preparing_video", title = video.title).to_string();
-        
-        // 중지 채널 생성
-        let (stop_tx, stop_rx) = channel();
-        self.stop_tx = Some(stop_tx);
-        
+    /// 프록시 입력칸 적용 (잘못된 주소면 이전 설정을 유지하고 false)
+    fn apply_proxy(&mut self) -> bool {
+        let proxy = rust_yt::network::normalize_proxy(&self.proxy_input);
+        if let Some(p) = &proxy
+            && let Err(e) = rust_yt::network::validate_proxy(p)
+        {
+            self.proxy_status = Some(Err(e.to_string()));
+            return false;
+        }
+        if proxy != self.proxy {
+            self.proxy_status = None;
+            self.thumbnails.set_proxy(proxy.clone());
+            self.proxy = proxy;
+            self.save_config();
+        }
+        true
+    }
+
+    fn test_proxy(&mut self) {
+        let proxy = self.proxy.clone();
+        let tx = self.tx_ui.clone();
+        self.proxy_testing = true;
+        self.proxy_status = None;
         thread::spawn(move || {
-            let (tx_internal, rx_internal) = channel();
-            
-            // 별도 스레드에서 다운로드 실행 (tx_internal 소유권 이동)
-            let config_clone = config.clone();
-            let title_clone = video.title.clone();
-            let tx_internal_clone = tx_internal.clone();
-            
-            thread::spawn(move || {
-                download_video(config_clone, title_clone, tx_internal_clone, stop_rx);
-            });
+            let result = rust_yt::network::test_connection(proxy.as_deref(), rust_yt::network::CONNECTION_TEST_URL);
+            let _ = tx.send(UiMessage::ProxyTested(result));
+        });
+    }
 
-            // 중계 루프
-            while let Ok(status) = rx_internal.recv() {
-                 match tx.send(UiMessage::DownloadProgress(status)) {
-                     Ok(_) => {},
-                     Err(_) => break, // UI가 닫히면 종료
-                 }
+    /// yt-dlp/ffmpeg 경로와 자동 설치 설정 (설정 영역과 도구 없음 화면에서 공용)
+    fn tools_settings_ui(&mut self, ui: &mut egui::Ui) {
+        let prev = (self.ytdlp_path.clone(), self.ffmpeg_path.clone(), self.use_system_tools);
+        egui::Grid::new("tools_grid").num_columns(2).show(ui, |ui| {
+            for (tool, path) in [(Tool::YtDlp, &mut self.ytdlp_path), (Tool::Ffmpeg, &mut self.ffmpeg_path)] {
+                ui.label(format!("{}:", tool.name()));
+                ui.horizontal(|ui| {
+                    match path {
+                        Some(p) => ui.monospace(p.display().to_string()),
+                        None => ui.weak(rust_i18n::t!("main.path_auto")),
+                    };
+                    if ui.button(rust_i18n::t!("main.browse_btn")).clicked()
+                        && let Some(picked) = rfd::FileDialog::new().pick_file()
+                    {
+                        *path = Some(picked);
+                    }
+                    if path.is_some() && ui.button(rust_i18n::t!("main.clear_btn")).clicked() {
+                        *path = None;
+                    }
+                });
+                ui.end_row();
             }
         });
+        ui.checkbox(&mut self.use_system_tools, rust_i18n::t!("main.use_system_tools"));
+        ui.weak(rust_i18n::t!("main.tools_hint"));
+
+        if prev != (self.ytdlp_path.clone(), self.ffmpeg_path.clone(), self.use_system_tools) {
+            rust_yt::binaries::set_configured_paths(self.ytdlp_path.clone(), self.ffmpeg_path.clone());
+            self.save_config();
+        }
     }
 
     fn save_config(&self) {
         let config = AppConfig {
             download_dir: Some(self.download_dir.clone()),
             format: AppConfig::format_to_string(&self.format),
-            audio_quality: "320K".to_string(),
+            audio_quality: self.audio_quality.clone(),
+            sample_rate: self.sample_rate,
+            bit_depth: self.bit_depth,
+            max_height: self.max_height,
+            max_fps: self.max_fps,
+            video_codec: self.video_codec,
+            output_template: self.output_template.clone(),
+            playlist_subfolder: self.playlist_subfolder,
+            subtitles: self.subtitles.clone(),
+            sponsorblock: self.sponsorblock.clone(),
+            auth: self.auth.clone(),
             language: rust_i18n::locale().to_string(),
+            max_concurrent_downloads: self.max_concurrent_downloads,
+            proxy: self.proxy.clone(),
+            rate_limit: self.rate_limit,
+            sleep_interval: self.sleep_interval,
+            ytdlp_path: self.ytdlp_path.clone(),
+            ffmpeg_path: self.ffmpeg_path.clone(),
+            use_system_tools: self.use_system_tools,
         };
         let _ = config.save();
     }
@@ -337,64 +674,101 @@ impl eframe::App for MyApp {
                             self.init_status = msg;
                             self.init_progress = 1.0; // 인디터미네이트로 쓸 수도 있음
                         }
+                        rust_yt::initializer::InitStatus::Verified(file, hash) => {
+                            self.init_status = rust_yt::initializer::InitStatus::verification_message(&file, hash.as_deref());
+                            self.init_progress = 1.0;
+                        }
+                        rust_yt::initializer::InitStatus::Missing(tools) => {
+                            self.state = AppState::ToolsMissing(tools);
+                        }
                         rust_yt::initializer::InitStatus::Completed => {
                             if self.skip_set_path {
                                 self.state = AppState::Input;
                             } else {
                                 self.state = AppState::SetPath;
                             }
+                            // 이전 실행에서 끝나지 않은 큐가 있으면 이어받기 제안
+                            self.pending_resume = SavedQueue::load()
+                                .filter(|saved| saved.unfinished().next().is_some());
                         }
                         rust_yt::initializer::InitStatus::Failed(e) => {
-                            self.error_msg = Some(format!("초기화 실패: {}", e));
+                            self.error_msg = Some(rust_i18n::t!("main.init_failed", error = e).to_string());
                             // 실패해도 일단 진행? 아니면 재시도? 일단 진행시켜서 수동 설정 유도하거나 에러 표시
                             self.state = AppState::SetPath; 
                         }
                     }
                 }
-                UiMessage::AnalysisDone(result) => {
+                UiMessage::AnalysisDone(id, _) | UiMessage::FormatsLoaded(id, _) if id != self.analysis_id => {}
+                UiMessage::AnalysisDone(_, result) => {
                     match result {
-                        Ok(info) => {
+                        Ok(mut info) => {
+                            self.formats_loading = !info.is_playlist;
+                            // 지금 고른 형식으로 이미 받은 영상은 기본으로 선택 해제
+                            info.select_for_format(&self.format);
                             self.playlist_info = Some(info);
                             self.state = AppState::Ready;
                         }
                         Err(e) => {
-                            self.error_msg = Some(e);
+                            self.auth_prompt |= matches!(e, Error::AuthRequired(_));
+                            self.error_msg = Some(error_message(&e));
                             self.state = AppState::Input;
                         }
                     }
                 }
-                UiMessage::DownloadProgress(status) => {
-                    match status {
-                        DownloadStatus::Starting(msg) => {
-                            self.progress_text = msg;
-                            self.progress = 0.0;
-                        }
-                        DownloadStatus::Progress(p, speed) => {
-                            self.progress = p / 100.0;
-                            self.progress_text = format!("{:.1}% ({})", p, speed);
-                        }
-                        DownloadStatus::Converting => {
-                            self.progress_text = rust_i18n::t!("main.converting").to_string();
-                        }
-                        DownloadStatus::Completed(_) => {
-                            self.current_download_idx += 1;
-                            self.download_next();
+                UiMessage::ProxyTested(result) => {
+                    self.proxy_testing = false;
+                    self.proxy_status = Some(match result {
+                        Ok(elapsed) => Ok(rust_i18n::t!("main.proxy_test_ok", ms = elapsed.as_millis()).to_string()),
+                        Err(e) => Err(error_message(&e)),
+                    });
+                }
+                UiMessage::FormatsLoaded(_, result) => {
+                    self.formats_loading = false;
+                    match result {
+                        Ok(formats) => self.formats = formats,
+                        Err(e) => self.formats_error = Some(error_message(&e)),
+                    }
+                }
+                UiMessage::Queue(event) => {
+                    match event {
+                        QueueEvent::Updated(idx, state, progress) => {
+                            let finished = state.is_finished();
+                            self.auth_prompt |= matches!(state, ItemState::Failed(Error::AuthRequired(_), _));
+                            if state == ItemState::Completed {
+                                self.mark_downloaded(idx);
+                            }
+                            // 디스크 부족/요청 제한은 다른 항목도 실패하므로 큐 전체 중지
+                            if let ItemState::Failed(e @ (Error::DiskFull | Error::RateLimited), _) = &state {
+                                self.error_msg = Some(error_message(e));
+                                self.stop_download();
+                            }
+                            if let Some(slot) = self.queue_states.get_mut(idx) {
+                                *slot = state;
+                            }
+                            self.queue_progress = progress;
+                            if finished {
+                                self.save_queue();
+                            }
                         }
-                        DownloadStatus::Failed(e) => {
-                            if self.progress_text == rust_i18n::t!("main.download_stopped").to_string() {
+                        QueueEvent::Finished(progress) => {
+                            self.queue_progress = progress;
+                            self.queue_handle = None;
+                            if self.queue_progress.completed == self.queue_progress.total {
+                                SavedQueue::clear();
+                            } else {
+                                self.save_queue();
+                            }
+                            if self.queue_progress.stopped > 0 || self.queue_progress.finished() < self.queue_progress.total {
+                                // 중지로 남은 항목이 있음
                                 self.state = AppState::Ready;
                                 self.progress_text = rust_i18n::t!("main.download_stopped").to_string();
                             } else {
-                                self.progress_text = format!("오류: {}", e);
-                                self.error_msg = Some(rust_i18n::t!("main.download_paused", error = e).to_string());
-                                self.state = AppState::Ready;
+                                self.state = AppState::Finished;
+                                self.progress_text = rust_i18n::t!("main.all_completed").to_string();
+                            }
+                            if self.queue_progress.failed > 0 {
+                                self.error_msg = Some(rust_i18n::t!("main.failed_count", count = self.queue_progress.failed).to_string());
                             }
-                            self.stop_tx = None;
-                        }
-                        DownloadStatus::Stopped => {
-                            self.state = AppState::Ready;
-                            self.progress_text = rust_i18n::t!("main.download_stopped").to_string();
-                            self.stop_tx = None;
                         }
                     }
                 }
@@ -433,6 +807,35 @@ impl eframe::App for MyApp {
             return;
         }
 
+        // -0. 실행 파일 없음 (자동 설치 꺼짐 또는 지정 경로 오류)
+        if let AppState::ToolsMissing(tools) = &self.state {
+            let messages: Vec<String> = tools.iter().map(|&tool| rust_yt::binaries::missing_message(tool)).collect();
+            egui::CentralPanel::default().show(ctx, |ui| {
+                ui.vertical_centered(|ui| {
+                    ui.add_space(60.0);
+                    ui.heading(rust_i18n::t!("initialization.missing_title"));
+                    ui.add_space(15.0);
+                    for message in &messages {
+                        ui.colored_label(egui::Color32::RED, message);
+                    }
+                    ui.add_space(10.0);
+                    ui.label(rust_i18n::t!("initialization.missing_hint"));
+                });
+                ui.add_space(15.0);
+                self.tools_settings_ui(ui);
+                ui.add_space(15.0);
+                ui.vertical_centered(|ui| {
+                    if ui.button(rust_i18n::t!("initialization.retry_btn")).clicked() {
+                        self.init_status = rust_i18n::t!("initialization.preparing").to_string();
+                        self.init_progress = 0.0;
+                        self.state = AppState::Initializing;
+                        spawn_init(self.tx_ui.clone(), self.proxy.clone(), !self.use_system_tools);
+                    }
+                });
+            });
+            return;
+        }
+
         // 0. 초기 경로 설정 화면
         if matches!(self.state, AppState::SetPath) {
              egui::CentralPanel::default().show(ctx, |ui| {
@@ -442,13 +845,13 @@ impl eframe::App for MyApp {
                     ui.add_space(50.0);
                     ui.label(rust_i18n::t!("main.select_folder_msg"));
                     ui.add_space(20.0);
-                    if ui.button(rust_i18n::t!("main.select_folder_btn")).clicked() {
-                         if let Some(path) = rfd::FileDialog::new().pick_folder() {
-                            self.download_dir = path.clone();
-                            self.state = AppState::Input;
-                            // 설정 저장
-                            self.save_config();
-                        }
+                    if ui.button(rust_i18n::t!("main.select_folder_btn")).clicked()
+                        && let Some(path) = rfd::FileDialog::new().pick_folder()
+                    {
+                        self.download_dir = path.clone();
+                        self.state = AppState::Input;
+                        // 설정 저장
+                        self.save_config();
                     }
                 });
             });
@@ -494,26 +897,51 @@ impl eframe::App for MyApp {
             // 경로 등
             ui.horizontal(|ui| {
                 ui.label(rust_i18n::t!("main.save_path", path = self.download_dir.display()));
-                if ui.button(rust_i18n::t!("main.change_btn")).clicked() {
-                    if let Some(path) = rfd::FileDialog::new().pick_folder() {
-                        self.download_dir = path.clone();
-                        // 설정 저장
-                        self.save_config();
-                    }
+                if ui.button(rust_i18n::t!("main.change_btn")).clicked()
+                    && let Some(path) = rfd::FileDialog::new().pick_folder()
+                {
+                    self.download_dir = path.clone();
+                    // 설정 저장
+                    self.save_config();
                 }
             });
+
+            // 파일명 템플릿 + 미리보기
+            ui.horizontal(|ui| {
+                ui.label(rust_i18n::t!("main.template_label"));
+                let placeholders = PLACEHOLDERS.iter().map(|p| format!("{{{}}}", p)).collect::<Vec<_>>().join(" ");
+                let response = ui.add(egui::TextEdit::singleline(&mut self.output_template).desired_width(320.0))
+                    .on_hover_text(rust_i18n::t!("main.template_hint", placeholders = placeholders));
+                if response.lost_focus() {
+                    self.save_config();
+                }
+                if ui.small_button(rust_i18n::t!("main.template_reset_btn")).clicked() {
+                    self.output_template = rust_yt::template::DEFAULT_OUTPUT_TEMPLATE.to_string();
+                    self.save_config();
+                }
+                if ui.checkbox(&mut self.playlist_subfolder, rust_i18n::t!("main.playlist_subfolder")).changed() {
+                    self.save_config();
+                }
+            });
+            match self.template_preview() {
+                Ok(preview) => {
+                    ui.label(egui::RichText::new(rust_i18n::t!("main.template_preview", path = preview.display())).weak());
+                }
+                Err(e) => {
+                    ui.colored_label(egui::Color32::RED, e.to_string());
+                }
+            }
             ui.separator();
 
             // URL 입력
             ui.horizontal(|ui| {
                 ui.label(rust_i18n::t!("main.url_label"));
                 let text_edit = ui.text_edit_singleline(&mut self.url);
-                if self.state.is_input() || matches!(self.state, AppState::Ready | AppState::Finished) {
-                    if ui.button(rust_i18n::t!("main.analyze_btn")).clicked() || (text_edit.lost_focus() && ctx.input(|i| i.key_pressed(egui::Key::Enter))) {
-                        if !self.url.trim().is_empty() {
-                            self.start_analysis();
-                        }
-                    }
+                if (self.state.is_input() || matches!(self.state, AppState::Ready | AppState::Finished))
+                    && (ui.button(rust_i18n::t!("main.analyze_btn")).clicked() || (text_edit.lost_focus() && ctx.input(|i| i.key_pressed(egui::Key::Enter))))
+                    && !self.url.trim().is_empty()
+                {
+                    self.start_analysis();
                 }
             });
 
@@ -542,8 +970,302 @@ impl eframe::App for MyApp {
                         ui.selectable_value(&mut self.format, DownloadFormat::Webm, rust_i18n::t!("formats.video_webm"));
                     });
                 
-                // 포맷 변경 시 설정 저장
+                // 포맷 변경 시 설정 저장, 받은 영상 선택 다시 계산
                 if prev_format != self.format {
+                    // 새 컨테이너에 넣을 수 없는 코덱이면 자동으로
+                    if !self.video_codec.supports(&self.format) {
+                        self.video_codec = VideoCodec::Any;
+                    }
+                    if let Some(info) = &mut self.playlist_info {
+                        info.select_for_format(&self.format);
+                    }
+                    self.save_config();
+                }
+            });
+
+            // 음질 선택 (오디오 형식만)
+            if self.format.is_audio() {
+                ui.horizontal(|ui| {
+                    ui.label(rust_i18n::t!("main.quality_label"));
+                    let prev = (self.audio_quality.clone(), self.sample_rate, self.bit_depth);
+
+                    if self.format.is_lossless() {
+                        let original = rust_i18n::t!("main.quality_original");
+                        egui::ComboBox::from_id_salt("sample_rate_combo")
+                            .selected_text(self.sample_rate.map(|r| format!("{} Hz", r)).unwrap_or_else(|| original.to_string()))
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(&mut self.sample_rate, None, original.clone());
+                                for rate in SAMPLE_RATES {
+                                    ui.selectable_value(&mut self.sample_rate, Some(rate), format!("{} Hz", rate));
+                                }
+                            });
+                        egui::ComboBox::from_id_salt("bit_depth_combo")
+                            .selected_text(self.bit_depth.map(|d| format!("{}-bit", d)).unwrap_or_else(|| original.to_string()))
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(&mut self.bit_depth, None, original.clone());
+                                for depth in BIT_DEPTHS {
+                                    ui.selectable_value(&mut self.bit_depth, Some(depth), format!("{}-bit", depth));
+                                }
+                            });
+                    } else {
+                        egui::ComboBox::from_id_salt("audio_quality_combo")
+                            .selected_text(audio_quality_label(&self.audio_quality))
+                            .show_ui(ui, |ui| {
+                                for bitrate in AUDIO_BITRATES {
+                                    ui.selectable_value(&mut self.audio_quality, bitrate.to_string(), audio_quality_label(bitrate));
+                                }
+                                ui.separator();
+                                for level in AUDIO_VBR_LEVELS {
+                                    ui.selectable_value(&mut self.audio_quality, level.to_string(), audio_quality_label(level));
+                                }
+                            });
+                    }
+
+                    if prev != (self.audio_quality.clone(), self.sample_rate, self.bit_depth) {
+                        self.save_config();
+                    }
+                });
+            } else {
+                // 화질 선택 (영상 형식만)
+                ui.horizontal(|ui| {
+                    ui.label(rust_i18n::t!("main.video_quality_label"));
+                    let prev = (self.max_height, self.max_fps, self.video_codec);
+                    let best = rust_i18n::t!("main.video_best");
+                    let any = rust_i18n::t!("main.video_any");
+
+                    egui::ComboBox::from_id_salt("max_height_combo")
+                        .selected_text(self.max_height.map(|h| format!("≤ {}p", h)).unwrap_or_else(|| best.to_string()))
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut self.max_height, None, best.clone());
+                            for height in VIDEO_HEIGHTS {
+                                ui.selectable_value(&mut self.max_height, Some(height), format!("≤ {}p", height));
+                            }
+                        });
+                    egui::ComboBox::from_id_salt("max_fps_combo")
+                        .selected_text(self.max_fps.map(|f| format!("≤ {} fps", f)).unwrap_or_else(|| any.to_string()))
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut self.max_fps, None, any.clone());
+                            for fps in VIDEO_FPS {
+                                ui.selectable_value(&mut self.max_fps, Some(fps), format!("≤ {} fps", fps));
+                            }
+                        });
+                    let codec_label = |codec: VideoCodec| match codec {
+                        VideoCodec::Any => rust_i18n::t!("main.video_codec_any").to_string(),
+                        _ => codec.label().to_string(),
+                    };
+                    egui::ComboBox::from_id_salt("video_codec_combo")
+                        .selected_text(codec_label(self.video_codec))
+                        .show_ui(ui, |ui| {
+                            for codec in VideoCodec::for_format(&self.format) {
+                                ui.selectable_value(&mut self.video_codec, codec, codec_label(codec));
+                            }
+                        });
+
+                    if prev != (self.max_height, self.max_fps, self.video_codec) {
+                        self.save_config();
+                    }
+                });
+            }
+
+            // 자막
+            ui.horizontal(|ui| {
+                let prev = self.subtitles.clone();
+                ui.checkbox(&mut self.subtitles.enabled, rust_i18n::t!("main.subtitles_check"));
+                ui.add_enabled_ui(self.subtitles.enabled, |ui| {
+                    ui.label(rust_i18n::t!("main.subtitle_langs_label"));
+                    let response = ui.add(egui::TextEdit::singleline(&mut self.subtitle_langs_input)
+                        .desired_width(100.0)
+                        .hint_text(rust_i18n::t!("main.subtitle_langs_hint")));
+                    if response.changed() {
+                        self.subtitles.languages = SubtitleOptions::parse_languages(&self.subtitle_langs_input);
+                    }
+                    ui.add_enabled(
+                        !self.subtitles.languages.is_empty(),
+                        egui::Checkbox::new(&mut self.subtitles.include_auto, rust_i18n::t!("main.subtitle_auto")),
+                    ).on_disabled_hover_text(rust_i18n::t!("main.subtitle_auto_needs_lang"));
+                    if !self.format.is_audio() {
+                        ui.checkbox(&mut self.subtitles.embed, rust_i18n::t!("main.subtitle_embed"));
+                    }
+                    // 파일로 저장할 때만 형식 선택
+                    if self.format.is_audio() || !self.subtitles.embed {
+                        egui::ComboBox::from_id_salt("subtitle_format_combo")
+                            .selected_text(self.subtitles.format.as_str().to_uppercase())
+                            .show_ui(ui, |ui| {
+                                for format in [SubtitleFormat::Srt, SubtitleFormat::Vtt] {
+                                    ui.selectable_value(&mut self.subtitles.format, format, format.as_str().to_uppercase());
+                                }
+                            });
+                    }
+                });
+                if prev != self.subtitles {
+                    self.save_config();
+                }
+            });
+
+            // SponsorBlock: 분류마다 잘라내기/챕터 표시 선택
+            let active = self.sponsorblock.remove.len() + self.sponsorblock.mark.len();
+            egui::CollapsingHeader::new(rust_i18n::t!("main.sponsorblock_heading", count = active))
+                .id_salt("sponsorblock_settings")
+                .show(ui, |ui| {
+                    let prev = self.sponsorblock.clone();
+                    egui::Grid::new("sponsorblock_grid").striped(true).show(ui, |ui| {
+                        ui.label("");
+                        ui.strong(rust_i18n::t!("main.sponsorblock_remove"));
+                        ui.strong(rust_i18n::t!("main.sponsorblock_mark"));
+                        ui.end_row();
+
+                        for category in SponsorCategory::ALL {
+                            ui.label(sponsor_category_label(category));
+                            ui.add_enabled_ui(category.removable(), |ui| {
+                                toggle_category(ui, &mut self.sponsorblock.remove, category);
+                            });
+                            // 잘라낸 구간은 챕터로 표시할 수 없음
+                            let removed = self.sponsorblock.remove.contains(&category);
+                            ui.add_enabled_ui(!removed, |ui| {
+                                toggle_category(ui, &mut self.sponsorblock.mark, category);
+                            });
+                            ui.end_row();
+                        }
+                    });
+                    if prev != self.sponsorblock {
+                        self.save_config();
+                    }
+                });
+
+            // 인증 (쿠키 파일 또는 브라우저 쿠키)
+            let auth_open = std::mem::take(&mut self.auth_prompt).then_some(true);
+            egui::CollapsingHeader::new(if self.auth.is_configured() {
+                    rust_i18n::t!("main.auth_heading_on")
+                } else {
+                    rust_i18n::t!("main.auth_heading_off")
+                })
+                .id_salt("auth_settings")
+                .open(auth_open)
+                .show(ui, |ui| {
+                    let prev = self.auth.clone();
+                    ui.horizontal(|ui| {
+                        ui.label(rust_i18n::t!("main.cookies_file_label"));
+                        match &self.auth.cookies_file {
+                            Some(path) => ui.monospace(path.display().to_string()),
+                            None => ui.weak(rust_i18n::t!("main.cookies_none")),
+                        };
+                        if ui.button(rust_i18n::t!("main.browse_btn")).clicked()
+                            && let Some(path) = rfd::FileDialog::new().add_filter("cookies.txt", &["txt"]).pick_file()
+                        {
+                            self.auth.cookies_file = Some(path);
+                        }
+                        if self.auth.cookies_file.is_some() && ui.button(rust_i18n::t!("main.clear_btn")).clicked() {
+                            self.auth.cookies_file = None;
+                        }
+                    });
+                    // 쿠키 파일이 있으면 브라우저 설정은 쓰지 않음
+                    ui.add_enabled_ui(self.auth.cookies_file.is_none(), |ui| {
+                        ui.horizontal(|ui| {
+                            ui.label(rust_i18n::t!("main.cookies_browser_label"));
+                            egui::ComboBox::from_id_salt("cookies_browser_combo")
+                                .selected_text(self.auth.cookies_browser.clone().unwrap_or_else(|| rust_i18n::t!("main.cookies_none").to_string()))
+                                .show_ui(ui, |ui| {
+                                    ui.selectable_value(&mut self.auth.cookies_browser, None, rust_i18n::t!("main.cookies_none"));
+                                    for browser in BROWSERS {
+                                        ui.selectable_value(&mut self.auth.cookies_browser, Some(browser.to_string()), browser);
+                                    }
+                                });
+                            if self.auth.cookies_browser.is_some() {
+                                ui.label(rust_i18n::t!("main.browser_profile_label"));
+                                let mut profile = self.auth.browser_profile.clone().unwrap_or_default();
+                                let response = ui.add(egui::TextEdit::singleline(&mut profile)
+                                    .desired_width(120.0)
+                                    .hint_text(rust_i18n::t!("main.browser_profile_hint")));
+                                if response.changed() {
+                                    self.auth.browser_profile = Some(profile).filter(|p| !p.trim().is_empty());
+                                }
+                            }
+                        });
+                    });
+                    if prev != self.auth {
+                        self.save_config();
+                    }
+                });
+
+            // 네트워크 (프록시)
+            egui::CollapsingHeader::new(match &self.proxy {
+                    Some(_) => rust_i18n::t!("main.network_heading_proxy"),
+                    None => rust_i18n::t!("main.network_heading_direct"),
+                })
+                .id_salt("network_settings")
+                .show(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label(rust_i18n::t!("main.proxy_label"));
+                        let response = ui.add(egui::TextEdit::singleline(&mut self.proxy_input)
+                            .desired_width(220.0)
+                            .hint_text("socks5://127.0.0.1:1080"));
+                        if response.lost_focus() {
+                            self.apply_proxy();
+                        }
+                        if ui.add_enabled(!self.proxy_testing, egui::Button::new(rust_i18n::t!("main.proxy_test_btn"))).clicked()
+                            && self.apply_proxy()
+                        {
+                            self.test_proxy();
+                        }
+                        if self.proxy_testing {
+                            ui.spinner();
+                        }
+                    });
+                    match &self.proxy_status {
+                        Some(Ok(msg)) => { ui.colored_label(egui::Color32::GREEN, msg); }
+                        Some(Err(msg)) => { ui.colored_label(egui::Color32::RED, msg); }
+                        None => { ui.weak(rust_i18n::t!("main.proxy_hint")); }
+                    }
+
+                    // 속도 제한 / 다운로드 사이 대기
+                    let prev = (self.rate_limit, self.sleep_interval);
+                    ui.horizontal(|ui| {
+                        ui.label(rust_i18n::t!("main.rate_limit_label"));
+                        let rate_label = |rate: Option<u32>| match rate {
+                            Some(kib) => rust_yt::downloader::format_rate_limit(kib),
+                            None => rust_i18n::t!("main.rate_unlimited").to_string(),
+                        };
+                        egui::ComboBox::from_id_salt("rate_limit_combo")
+                            .selected_text(rate_label(self.rate_limit))
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(&mut self.rate_limit, None, rate_label(None));
+                                for rate in RATE_LIMITS {
+                                    ui.selectable_value(&mut self.rate_limit, Some(rate), rate_label(Some(rate)));
+                                }
+                            });
+                    });
+                    ui.horizontal(|ui| {
+                        let mut enabled = self.sleep_interval.is_some();
+                        if ui.checkbox(&mut enabled, rust_i18n::t!("main.sleep_check")).changed() {
+                            self.sleep_interval = enabled.then_some((5, 15));
+                        }
+                        if let Some((min, max)) = &mut self.sleep_interval {
+                            ui.add(egui::DragValue::new(min).range(0..=MAX_SLEEP_SECS));
+                            ui.label("~");
+                            ui.add(egui::DragValue::new(max).range(*min..=MAX_SLEEP_SECS));
+                            ui.label(rust_i18n::t!("main.sleep_seconds"));
+                            *max = (*max).max(*min);
+                        }
+                    });
+                    if prev != (self.rate_limit, self.sleep_interval) {
+                        self.save_config();
+                    }
+                });
+
+            // yt-dlp/ffmpeg 위치
+            egui::CollapsingHeader::new(if self.use_system_tools {
+                    rust_i18n::t!("main.tools_heading_system")
+                } else {
+                    rust_i18n::t!("main.tools_heading_auto")
+                })
+                .id_salt("tools_settings")
+                .show(ui, |ui| self.tools_settings_ui(ui));
+
+            // 동시 다운로드 수
+            ui.horizontal(|ui| {
+                ui.label(rust_i18n::t!("main.parallel_label"));
+                let response = ui.add(egui::DragValue::new(&mut self.max_concurrent_downloads).range(1..=8));
+                if response.changed() {
                     self.save_config();
                 }
             });
@@ -570,6 +1292,21 @@ impl eframe::App for MyApp {
                 ui.separator();
             }
 
+            // 실패한 항목 상세 (yt-dlp stderr)
+            if let Some((count, details)) = self.failure_details() {
+                egui::CollapsingHeader::new(rust_i18n::t!("main.failure_details", count = count))
+                    .id_salt("failure_details")
+                    .show(ui, |ui| {
+                        if ui.button(rust_i18n::t!("main.copy_btn")).clicked() {
+                            ui.ctx().copy_text(details.clone());
+                        }
+                        egui::ScrollArea::vertical().max_height(150.0).show(ui, |ui| {
+                            ui.label(egui::RichText::new(&details).monospace().small());
+                        });
+                    });
+                ui.separator();
+            }
+
             // 다운로드 컨트롤
             match self.state {
                 AppState::Ready => {
@@ -585,23 +1322,32 @@ impl eframe::App for MyApp {
                     };
 
                     // 분석이 완료된 상태에서만 버튼 활성화
-                    if self.playlist_info.is_some() {
-                         if ui.button(btn_text).clicked() {
-                            if let Err(e) = self.start_download() {
-                                self.error_msg = Some(e);
-                            }
-                        }
+                    if self.playlist_info.is_some()
+                        && ui.button(btn_text).clicked()
+                        && let Err(e) = self.start_download()
+                    {
+                        self.error_msg = Some(e);
                     }
                 }
                 AppState::Downloading => {
-                    ui.label(rust_i18n::t!("main.downloading_status", current = self.current_download_idx + 1, total = self.download_queue.len()));
-                    if self.current_download_idx < self.download_queue.len() {
-                        ui.label(&self.download_queue[self.current_download_idx].title);
+                    ui.label(rust_i18n::t!("main.downloading_status", current = self.queue_progress.finished(), total = self.queue_progress.total));
+                    ui.add(egui::ProgressBar::new(self.queue_progress.fraction as f32).show_percentage());
+
+                    // 진행 중인 항목별 상태
+                    for (video, state) in self.download_queue.iter().zip(&self.queue_states) {
+                        if state.is_active() {
+                            ui.add_space(2.0);
+                            ui.label(format!("{} · {}", video.title, item_state_label(state)));
+                            if let ItemState::Downloading(p) = state {
+                                ui.add(egui::ProgressBar::new((p.percent / 100.0) as f32).desired_height(6.0));
+                            }
+                        }
+                    }
+
+                    if !self.progress_text.is_empty() {
+                        ui.add_space(5.0);
+                        ui.label(&self.progress_text);
                     }
-                    ui.add_space(5.0);
-                    ui.label(&self.progress_text);
-                    ui.add_space(2.0);
-                    ui.add(egui::ProgressBar::new(self.progress as f32).animate(true));
 
                     ui.add_space(5.0);
                     if ui.button(rust_i18n::t!("main.stop_download_btn")).clicked() {
@@ -622,8 +1368,7 @@ impl eframe::App for MyApp {
 
                         if ui.button(rust_i18n::t!("main.back_to_list_btn")).clicked() {
                             self.state = AppState::Ready;
-                            self.current_download_idx = 0;
-                            self.progress = 0.0;
+                            self.queue_states.clear();
                         }
                     });
                 }
@@ -633,6 +1378,14 @@ impl eframe::App for MyApp {
         });
 
         // 3. Central Panel (리스트)
+        // 큐에 들어간 영상의 상태 (id 기준)
+        let item_states: HashMap<&str, &ItemState> = self.download_queue.iter()
+            .map(|video| video.id.as_str())
+            .zip(&self.queue_states)
+            .collect();
+
+        let mut subtitles_changed = false;
+
         egui::CentralPanel::default().show(ctx, |ui| {
              if let Some(info) = &mut self.playlist_info {
                 ui.heading(&info.title);
@@ -654,6 +1407,7 @@ impl eframe::App for MyApp {
                 egui::ScrollArea::vertical().show(ui, |ui| {
                     if info.is_playlist {
                         for (idx, entry) in info.entries.iter_mut().enumerate() {
+                            let number = entry.playlist_index.unwrap_or(idx + 1);
                             ui.horizontal(|ui| {
                                 ui.checkbox(&mut entry.selected, "");
                                 
@@ -663,8 +1417,14 @@ impl eframe::App for MyApp {
                                 }
 
                                 ui.vertical(|ui| {
-                                    ui.label(format!("{}. {}", idx + 1, entry.title));
-                                    ui.label(egui::RichText::new(entry.format_duration()).weak());
+                                    ui.label(format!("{}. {}", number, entry.title));
+                                    ui.horizontal(|ui| {
+                                        ui.label(egui::RichText::new(entry.format_duration()).weak());
+                                        downloaded_label(ui, entry, &self.format);
+                                        if let Some(state) = item_states.get(entry.id.as_str()) {
+                                            ui.label(egui::RichText::new(item_state_label(state)).weak());
+                                        }
+                                    });
                                 });
                             });
                             ui.separator();
@@ -682,8 +1442,112 @@ impl eframe::App for MyApp {
                                 ui.vertical(|ui| {
                                     ui.label(rust_i18n::t!("main.video_title", title = entry.title));
                                     ui.label(rust_i18n::t!("main.video_duration", duration = entry.format_duration()));
+                                    downloaded_label(ui, entry, &self.format);
+                                    if let Some(state) = item_states.get(entry.id.as_str()) {
+                                        ui.label(egui::RichText::new(item_state_label(state)).weak());
+                                    }
                                 });
                             });
+
+                            // 일부 구간만 받기
+                            ui.add_space(5.0);
+                            ui.horizontal(|ui| {
+                                ui.label(rust_i18n::t!("main.clip_label"));
+                                ui.add(egui::TextEdit::singleline(&mut self.clip_start)
+                                    .desired_width(70.0)
+                                    .hint_text("0:00"));
+                                ui.label("–");
+                                ui.add(egui::TextEdit::singleline(&mut self.clip_end)
+                                    .desired_width(70.0)
+                                    .hint_text(entry.format_duration()));
+                                ui.checkbox(&mut self.precise_cuts, rust_i18n::t!("main.precise_cuts"))
+                                    .on_hover_text(rust_i18n::t!("main.precise_cuts_hint"));
+                            });
+                            if let Err(e) = TimeRange::parse(&self.clip_start, &self.clip_end, entry.duration) {
+                                ui.colored_label(egui::Color32::RED, e.to_string());
+                            }
+
+                            // 챕터 목록 + 나누기
+                            if !entry.chapters.is_empty() {
+                                ui.add_space(5.0);
+                                egui::CollapsingHeader::new(rust_i18n::t!("main.chapters_heading", count = entry.chapters.len()))
+                                    .default_open(true)
+                                    .show(ui, |ui| {
+                                        ui.checkbox(&mut self.split_chapters, rust_i18n::t!("main.split_chapters"))
+                                            .on_hover_text(rust_i18n::t!("main.split_chapters_hint"));
+                                        ui.add_enabled_ui(self.split_chapters, |ui| {
+                                            for (idx, chapter) in entry.chapters.iter_mut().enumerate() {
+                                                ui.horizontal(|ui| {
+                                                    ui.checkbox(&mut chapter.selected, "");
+                                                    ui.label(egui::RichText::new(format!("{:02}", idx + 1)).weak());
+                                                    ui.label(egui::RichText::new(chapter.format_start()).monospace());
+                                                    ui.label(&chapter.title);
+                                                    ui.label(egui::RichText::new(format!("({})", chapter.format_length())).weak());
+                                                });
+                                            }
+                                        });
+                                    });
+                            }
+
+                            // 받을 수 있는 자막 (누르면 받을 언어에 추가/제거)
+                            for auto in [false, true] {
+                                let tracks: Vec<_> = entry.subtitles.iter().filter(|s| s.auto == auto).collect();
+                                if tracks.is_empty() {
+                                    continue;
+                                }
+                                ui.horizontal_wrapped(|ui| {
+                                    ui.label(if auto {
+                                        rust_i18n::t!("main.available_auto_subtitles")
+                                    } else {
+                                        rust_i18n::t!("main.available_subtitles")
+                                    });
+                                    for track in tracks {
+                                        let selected = self.subtitles.languages.contains(&track.language);
+                                        let response = ui.selectable_label(selected, &track.language)
+                                            .on_hover_text(format!(
+                                                "{}\n{}",
+                                                track.name.as_deref().unwrap_or(&track.language),
+                                                rust_i18n::t!("main.subtitle_toggle_hint")
+                                            ));
+                                        if response.clicked() {
+                                            if selected {
+                                                self.subtitles.languages.retain(|l| l != &track.language);
+                                            } else {
+                                                self.subtitles.languages.push(track.language.clone());
+                                                self.subtitles.enabled = true;
+                                                self.subtitles.include_auto |= auto;
+                                            }
+                                            self.subtitle_langs_input = self.subtitles.languages.join(", ");
+                                            subtitles_changed = true;
+                                        }
+                                    }
+                                });
+                            }
+
+                            // 스트림 직접 선택
+                            ui.add_space(10.0);
+                            if self.formats_loading {
+                                ui.horizontal(|ui| {
+                                    ui.spinner();
+                                    ui.label(rust_i18n::t!("main.formats_loading"));
+                                });
+                            } else if let Some(e) = &self.formats_error {
+                                ui.colored_label(egui::Color32::RED, rust_i18n::t!("main.formats_failed", error = e));
+                            } else if !self.formats.is_empty() {
+                                egui::CollapsingHeader::new(rust_i18n::t!("main.formats_heading", count = self.formats.len()))
+                                    .default_open(false)
+                                    .show(ui, |ui| {
+                                        if !self.format.is_audio() {
+                                            ui.label(egui::RichText::new(rust_i18n::t!("main.video_streams")).strong());
+                                            let video_streams: Vec<&FormatInfo> = self.formats.iter().filter(|f| f.has_video()).collect();
+                                            format_table(ui, "video_formats_grid", &video_streams, &mut self.video_format_id);
+                                            ui.add_space(8.0);
+                                        }
+                                        ui.label(egui::RichText::new(rust_i18n::t!("main.audio_streams")).strong());
+                                        let audio_streams: Vec<&FormatInfo> = self.formats.iter().filter(|f| f.has_audio() && !f.has_video()).collect();
+                                        format_table(ui, "audio_formats_grid", &audio_streams, &mut self.audio_format_id);
+                                    });
+                            }
                         }
                     }
                 });
@@ -697,7 +1561,38 @@ impl eframe::App for MyApp {
                 }
             }
         });
+
+        if subtitles_changed {
+            self.save_config();
+        }
         
+        // 이어받기 확인 창
+        if self.state.is_input() && self.pending_resume.is_some() {
+            let mut resume = false;
+            let mut discard = false;
+            egui::Window::new(rust_i18n::t!("main.resume_title"))
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+                .show(ctx, |ui| {
+                    if let Some(saved) = &self.pending_resume {
+                        ui.label(rust_i18n::t!("main.resume_msg", title = saved.title, count = saved.unfinished().count()));
+                    }
+                    ui.add_space(10.0);
+                    ui.horizontal(|ui| {
+                        resume = ui.button(rust_i18n::t!("main.resume_btn")).clicked();
+                        discard = ui.button(rust_i18n::t!("main.discard_btn")).clicked();
+                    });
+                });
+
+            if resume && let Some(saved) = self.pending_resume.take() {
+                self.resume_saved_queue(saved);
+            } else if discard {
+                self.pending_resume = None;
+                SavedQueue::clear();
+            }
+        }
+
         // 애니메이션 효과를 위해 지속적 갱신 필요시 (다운로드 중일 때)
         if matches!(self.state, AppState::Downloading) {
              ctx.request_repaint();
@@ -712,6 +1607,130 @@ impl AppState {
     }
 }
 
-// download_next에서 스레드 생성시 channel 중계 로직 필요
-// downloader::download_video의 인자가 Sender<DownloadStatus> 라서
+/// 이미 받은 형식 표시 (현재 선택한 형식이면 강조)
+fn downloaded_label(ui: &mut egui::Ui, entry: &VideoEntry, format: &DownloadFormat) {
+    if entry.downloaded_formats.is_empty() {
+        return;
+    }
+    let formats = entry.downloaded_formats.iter()
+        .map(|f| AppConfig::format_to_string(f).to_uppercase())
+        .collect::<Vec<_>>()
+        .join(", ");
+    let text = rust_i18n::t!("main.already_downloaded", formats = formats);
+    if entry.downloaded_formats.contains(format) {
+        ui.colored_label(egui::Color32::from_rgb(80, 160, 80), text);
+    } else {
+        ui.label(egui::RichText::new(text).weak());
+    }
+}
+
+/// 스트림 목록 표 (라디오 버튼으로 하나 선택, "자동" 포함)
+fn format_table(ui: &mut egui::Ui, id_salt: &str, formats: &[&FormatInfo], selected: &mut Option<String>) {
+    egui::Grid::new(id_salt)
+        .striped(true)
+        .spacing([12.0, 4.0])
+        .show(ui, |ui| {
+            ui.label("");
+            ui.label(rust_i18n::t!("main.col_id"));
+            ui.label(rust_i18n::t!("main.col_ext"));
+            ui.label(rust_i18n::t!("main.col_resolution"));
+            ui.label(rust_i18n::t!("main.col_codec"));
+            ui.label(rust_i18n::t!("main.col_bitrate"));
+            ui.label(rust_i18n::t!("main.col_size"));
+            ui.label(rust_i18n::t!("main.col_note"));
+            ui.end_row();
+
+            ui.radio_value(selected, None, "");
+            ui.label(rust_i18n::t!("main.format_auto"));
+            ui.end_row();
+
+            for f in formats {
+                ui.radio_value(selected, Some(f.format_id.clone()), "");
+                ui.label(&f.format_id);
+                ui.label(&f.ext);
+                let resolution = match f.fps {
+                    Some(fps) if f.has_video() => format!("{} {:.0}fps", f.resolution_string(), fps),
+                    _ => f.resolution_string(),
+                };
+                ui.label(resolution);
+                ui.label(f.codec_string());
+                ui.label(f.bitrate_string());
+                ui.label(f.size_string());
+                let note = [f.format_note.as_deref(), f.language.as_deref()]
+                    .into_iter()
+                    .flatten()
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                ui.label(egui::RichText::new(note).weak());
+                ui.end_row();
+            }
+        });
+}
+
+/// 음질 선택지 표시 ("320K" → "320 kbps", "0" → "VBR 0 (최고)")
+fn audio_quality_label(quality: &str) -> String {
+    match quality.strip_suffix('K') {
+        Some(kbps) => format!("{} kbps", kbps),
+        None if quality == "0" => rust_i18n::t!("main.quality_vbr_best", level = quality).to_string(),
+        None => rust_i18n::t!("main.quality_vbr", level = quality).to_string(),
+    }
+}
+
+fn sponsor_category_label(category: SponsorCategory) -> String {
+    match category {
+        SponsorCategory::Sponsor => rust_i18n::t!("sponsorblock.sponsor"),
+        SponsorCategory::Intro => rust_i18n::t!("sponsorblock.intro"),
+        SponsorCategory::Outro => rust_i18n::t!("sponsorblock.outro"),
+        SponsorCategory::Selfpromo => rust_i18n::t!("sponsorblock.selfpromo"),
+        SponsorCategory::Preview => rust_i18n::t!("sponsorblock.preview"),
+        SponsorCategory::Filler => rust_i18n::t!("sponsorblock.filler"),
+        SponsorCategory::Interaction => rust_i18n::t!("sponsorblock.interaction"),
+        SponsorCategory::MusicOfftopic => rust_i18n::t!("sponsorblock.music_offtopic"),
+        SponsorCategory::PoiHighlight => rust_i18n::t!("sponsorblock.poi_highlight"),
+    }.to_string()
+}
+
+/// 분류 목록에 넣고 빼는 체크박스
+fn toggle_category(ui: &mut egui::Ui, categories: &mut Vec<SponsorCategory>, category: SponsorCategory) {
+    let mut checked = categories.contains(&category);
+    if ui.checkbox(&mut checked, "").changed() {
+        if checked {
+            categories.push(category);
+        } else {
+            categories.retain(|c| *c != category);
+        }
+    }
+}
+
+/// 오류 메시지 + 종류별 해결 안내
+fn error_message(e: &Error) -> String {
+    let hint = match e {
+        Error::YtDlpNotFound(_) => Some(rust_i18n::t!("errors.hint_ytdlp_not_found")),
+        Error::FfmpegMissing => Some(rust_i18n::t!("errors.hint_ffmpeg_missing")),
+        Error::AuthRequired(_) => Some(rust_i18n::t!("errors.hint_auth_required")),
+        Error::RateLimited => Some(rust_i18n::t!("errors.hint_rate_limited")),
+        Error::DiskFull => Some(rust_i18n::t!("errors.hint_disk_full")),
+        _ => None,
+    };
+    match hint {
+        Some(hint) => format!("{}\n{}", e, hint),
+        None => e.to_string(),
+    }
+}
+
+fn item_state_label(state: &ItemState) -> String {
+    match state {
+        ItemState::Pending => rust_i18n::t!("queue.pending").to_string(),
+        ItemState::Waiting(secs) => rust_i18n::t!("queue.waiting", secs = secs).to_string(),
+        ItemState::Starting => rust_i18n::t!("queue.starting").to_string(),
+        ItemState::Downloading(p) => rust_i18n::t!("queue.downloading", percent = format!("{:.1}", p.percent), speed = p.speed_string(), eta = p.eta_string()).to_string(),
+        ItemState::Converting => rust_i18n::t!("main.converting").to_string(),
+        ItemState::Completed => rust_i18n::t!("queue.completed").to_string(),
+        ItemState::Failed(e, _) => rust_i18n::t!("queue.failed", error = e).to_string(),
+        ItemState::Stopped => rust_i18n::t!("main.download_stopped").to_string(),
+    }
+}
+
+// start_queue에서 스레드 생성시 channel 중계 로직 필요
+// queue::run_queue의 인자가 Sender<QueueEvent> 라서
 // UiMessage로 감싸주는 래퍼가 필요.
\ No newline at end of file
@@ -1,4 +1,11 @@
+use crate::auth::AuthOptions;
+use crate::binaries::{resolve, Tool};
+use crate::downloader::{format_bytes, DownloadFormat};
+use crate::error::{classify_ytdlp_stderr, Error};
+use crate::history::DownloadHistory;
+use crate::network::proxy_args;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::process::Command;
 
 /// 플레이리스트 또는 단일 영상 정보
@@ -9,6 +16,23 @@ pub struct PlaylistInfo {
     pub is_playlist: bool,
 }
 
+impl PlaylistInfo {
+    /// 가장 큰 플레이리스트 번호 (트랙 번호 자릿수 계산용)
+    pub fn last_index(&self) -> usize {
+        self.entries.iter().filter_map(|e| e.playlist_index).max().unwrap_or(self.entries.len())
+    }
+
+    /// 플레이리스트에서 이미 `format`으로 받은 영상은 선택 해제 (단일 영상은 직접 입력한 URL이므로 유지)
+    pub fn select_for_format(&mut self, format: &DownloadFormat) {
+        if !self.is_playlist {
+            return;
+        }
+        for entry in &mut self.entries {
+            entry.selected = !entry.downloaded_formats.contains(format);
+        }
+    }
+}
+
 /// 개별 영상 정보
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VideoEntry {
@@ -19,7 +43,17 @@ pub struct VideoEntry {
     pub duration: Option<f64>,
     pub duration_string: Option<String>,
     #[serde(default)]
+    pub uploader: Option<String>, // 업로더/채널 이름
+    #[serde(default)]
+    pub subtitles: Vec<SubtitleTrack>, // 받을 수 있는 자막 (단일 영상 분석 시에만 채워짐)
+    #[serde(default)]
+    pub chapters: Vec<Chapter>,        // 챕터 (단일 영상 분석 시에만 채워짐)
+    #[serde(default)]
+    pub playlist_index: Option<usize>, // 플레이리스트 내 실제 위치 (1부터, 단일 영상은 None)
+    #[serde(default)]
     pub selected: bool,
+    #[serde(default)]
+    pub downloaded_formats: Vec<DownloadFormat>, // 이미 받은 형식 (다운로드 기록)
 }
 
 impl VideoEntry {
@@ -36,6 +70,174 @@ impl VideoEntry {
     }
 }
 
+/// 영상 챕터
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Chapter {
+    #[serde(default)]
+    pub title: String,
+    pub start_time: f64,
+    pub end_time: f64,
+    #[serde(default = "default_true")]
+    pub selected: bool, // 챕터 나누기 시 남길지
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Chapter {
+    /// "1:02:03" / "2:05" 형식 시작 시각
+    pub fn format_start(&self) -> String {
+        format_timestamp(self.start_time)
+    }
+
+    /// 길이 표시
+    pub fn format_length(&self) -> String {
+        format_timestamp(self.end_time - self.start_time)
+    }
+}
+
+/// 초 → "H:MM:SS" / "M:SS"
+pub fn format_timestamp(seconds: f64) -> String {
+    let total = seconds.max(0.0) as u64;
+    let (hours, mins, secs) = (total / 3600, total % 3600 / 60, total % 60);
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, mins, secs)
+    } else {
+        format!("{}:{:02}", mins, secs)
+    }
+}
+
+/// "1:02:03.5" / "2:05" / "90" → 초
+pub fn parse_timestamp(input: &str) -> Option<f64> {
+    let input = input.trim();
+    if input.is_empty() {
+        return None;
+    }
+
+    let mut seconds = 0.0;
+    let parts: Vec<&str> = input.split(':').collect();
+    if parts.len() > 3 {
+        return None;
+    }
+    for (idx, part) in parts.iter().enumerate() {
+        let value: f64 = part.trim().parse().ok().filter(|v: &f64| v.is_finite() && *v >= 0.0)?;
+        // 시/분 단위는 정수, 분/초 단위는 60 미만이어야 함
+        let is_last = idx == parts.len() - 1;
+        if (!is_last && value.fract() != 0.0) || (idx > 0 && value >= 60.0) {
+            return None;
+        }
+        seconds = seconds * 60.0 + value;
+    }
+    Some(seconds)
+}
+
+/// 받을 수 있는 자막 언어
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SubtitleTrack {
+    pub language: String, // yt-dlp 언어 코드 (`--sub-langs`에 그대로 사용)
+    pub name: Option<String>,
+    pub auto: bool, // 자동 생성 자막
+}
+
+/// yt-dlp `subtitles`/`automatic_captions` 항목의 개별 파일
+#[derive(Debug, Deserialize)]
+struct YtDlpSubtitle {
+    #[serde(default)]
+    name: Option<String>,
+}
+
+/// 언어 코드 → 파일 목록 맵을 언어 순으로 정리
+fn subtitle_tracks(subtitles: HashMap<String, Vec<YtDlpSubtitle>>, auto: bool) -> Vec<SubtitleTrack> {
+    let mut tracks: Vec<SubtitleTrack> = subtitles.into_iter()
+        .filter(|(language, _)| language != "live_chat")
+        .map(|(language, files)| SubtitleTrack {
+            name: files.into_iter().find_map(|f| f.name),
+            language,
+            auto,
+        })
+        .collect();
+    tracks.sort_by(|a, b| a.language.cmp(&b.language));
+    tracks
+}
+
+/// 영상 하나에서 받을 수 있는 개별 스트림 (yt-dlp `formats` 항목)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FormatInfo {
+    pub format_id: String,
+    #[serde(default)]
+    pub ext: String,
+    #[serde(default)]
+    pub format_note: Option<String>,
+    #[serde(default)]
+    pub width: Option<u32>,
+    #[serde(default)]
+    pub height: Option<u32>,
+    #[serde(default)]
+    pub fps: Option<f64>,
+    #[serde(default)]
+    pub vcodec: Option<String>, // 영상 없는 스트림은 "none"
+    #[serde(default)]
+    pub acodec: Option<String>, // 소리 없는 스트림은 "none"
+    #[serde(default)]
+    pub tbr: Option<f64>, // 전체 비트레이트 (kbps)
+    #[serde(default)]
+    pub filesize: Option<u64>,
+    #[serde(default)]
+    pub filesize_approx: Option<u64>,
+    #[serde(default)]
+    pub language: Option<String>,
+}
+
+impl FormatInfo {
+    fn has_codec(codec: &Option<String>) -> bool {
+        codec.as_deref().is_some_and(|c| !c.is_empty() && c != "none")
+    }
+
+    /// 영상 스트림이 포함되어 있는지
+    pub fn has_video(&self) -> bool {
+        Self::has_codec(&self.vcodec)
+    }
+
+    /// 소리 스트림이 포함되어 있는지
+    pub fn has_audio(&self) -> bool {
+        Self::has_codec(&self.acodec)
+    }
+
+    /// "1920x1080" 형식 해상도 (오디오 전용이면 "audio only")
+    pub fn resolution_string(&self) -> String {
+        match (self.width, self.height) {
+            (Some(w), Some(h)) => format!("{}x{}", w, h),
+            (None, Some(h)) => format!("{}p", h),
+            _ if !self.has_video() => "audio only".to_string(),
+            _ => "?".to_string(),
+        }
+    }
+
+    /// 코덱 표시 ("avc1.640028 + mp4a.40.2")
+    pub fn codec_string(&self) -> String {
+        [&self.vcodec, &self.acodec].into_iter()
+            .filter(|c| Self::has_codec(c))
+            .filter_map(|c| c.as_deref())
+            .collect::<Vec<_>>()
+            .join(" + ")
+    }
+
+    /// 파일 크기 표시 (추정치는 "~" 접두)
+    pub fn size_string(&self) -> String {
+        match (self.filesize, self.filesize_approx) {
+            (Some(size), _) => format_bytes(size),
+            (None, Some(size)) => format!("~{}", format_bytes(size)),
+            (None, None) => "-".to_string(),
+        }
+    }
+
+    /// 비트레이트 표시
+    pub fn bitrate_string(&self) -> String {
+        self.tbr.map(|tbr| format!("{:.0} kbps", tbr)).unwrap_or_else(|| "-".to_string())
+    }
+}
+
 /// yt-dlp JSON 응답 파싱용 구조체
 #[derive(Debug, Deserialize)]
 struct YtDlpResponse {
@@ -52,11 +254,28 @@ struct YtDlpResponse {
     #[serde(default)]
     duration_string: Option<String>,
     #[serde(default)]
+    uploader: Option<String>,
+    #[serde(default)]
+    channel: Option<String>,
+    #[serde(default)]
+    subtitles: HashMap<String, Vec<YtDlpSubtitle>>,
+    #[serde(default)]
+    automatic_captions: HashMap<String, Vec<YtDlpSubtitle>>,
+    #[serde(default)]
+    chapters: Option<Vec<Chapter>>, // 챕터가 없으면 null
+    #[serde(default)]
     entries: Option<Vec<YtDlpEntry>>,
     #[serde(rename = "_type", default)]
     response_type: Option<String>,
 }
 
+/// 스트림 목록만 필요한 경우의 응답
+#[derive(Debug, Deserialize)]
+struct YtDlpFormatsResponse {
+    #[serde(default)]
+    formats: Vec<FormatInfo>,
+}
+
 #[derive(Debug, Deserialize)]
 struct YtDlpEntry {
     #[serde(default)]
@@ -71,43 +290,18 @@ struct YtDlpEntry {
     duration: Option<f64>,
     #[serde(default)]
     duration_string: Option<String>,
+    #[serde(default)]
+    uploader: Option<String>,
+    #[serde(default)]
+    channel: Option<String>,
 }
 
-/// yt-dlp 경로 가져오기
-pub fn get_ytdlp_path() -> std::path::PathBuf {
-    #[cfg(target_os = "windows")]
-    {
-        let app_dir = dirs::data_local_dir()
-            .unwrap_or_else(|| std::path::PathBuf::from("."))
-            .join("rust-yt");
-        app_dir.join("yt-dlp.exe")
-    }
-    #[cfg(target_os = "macos")]
-    {
-        std::path::PathBuf::from("yt-dlp")
-    }
-    #[cfg(all(not(target_os = "windows"), not(target_os = "macos")))]
-    {
-        let home = std::env::var("HOME").unwrap_or_else(|_| String::from("/home"));
-        let pipx_path = std::path::PathBuf::from(format!("{}/.local/bin/yt-dlp", home));
-        if pipx_path.exists() {
-            return pipx_path;
-        }
-        std::path::PathBuf::from("yt-dlp")
-    }
-}
+/// yt-dlp를 JSON 모드(`-J`)로 실행해 응답 파싱
+fn run_ytdlp_json<T: serde::de::DeserializeOwned>(args: &[&str], auth: &AuthOptions, proxy: Option<&str>) -> Result<T, Error> {
+    let ytdlp = resolve(Tool::YtDlp)?.path;
 
-/// URL에서 플레이리스트/영상 정보 가져오기
-pub fn fetch_playlist_info(url: &str) -> Result<PlaylistInfo, String> {
-    let ytdlp = get_ytdlp_path();
-    
     let mut command = Command::new(&ytdlp);
-    command.args([
-            "--flat-playlist",
-            "-J",
-            "--no-warnings",
-            url,
-        ]);
+    command.args(auth.args()).args(proxy_args(proxy)).args(args);
 
     #[cfg(target_os = "windows")]
     {
@@ -117,58 +311,92 @@ pub fn fetch_playlist_info(url: &str) -> Result<PlaylistInfo, String> {
     }
 
     let output = command.output()
-        .map_err(|e| format!("yt-dlp 실행 실패: {}", e))?;
+        .map_err(|e| Error::YtDlpNotFound(format!("{}: {}", ytdlp.display(), e)))?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("영상 정보를 가져올 수 없습니다: {}", stderr));
+        return Err(classify_ytdlp_stderr(&stderr));
     }
 
     let json_str = String::from_utf8_lossy(&output.stdout);
-    let response: YtDlpResponse = serde_json::from_str(&json_str)
-        .map_err(|e| format!("JSON 파싱 실패: {}", e))?;
+    Ok(serde_json::from_str(&json_str)?)
+}
+
+/// URL에서 플레이리스트/영상 정보 가져오기
+pub fn fetch_playlist_info(url: &str, auth: &AuthOptions, proxy: Option<&str>) -> Result<PlaylistInfo, Error> {
+    let response: YtDlpResponse = run_ytdlp_json(&["--flat-playlist", "-J", "--no-warnings", url], auth, proxy)?;
 
     let is_playlist = response.response_type.as_deref() == Some("playlist");
+    let history = DownloadHistory::load();
+    let response_uploader = response.uploader.clone().or(response.channel.clone());
     
     if is_playlist {
         // 플레이리스트
+        // id가 없어 건너뛴 항목이 있어도 번호는 원래 플레이리스트 위치를 따름
         let entries = response.entries.unwrap_or_default()
             .into_iter()
-            .filter_map(|e| {
+            .enumerate()
+            .filter_map(|(idx, e)| {
                 let id = e.id?;
                 Some(VideoEntry {
+                    downloaded_formats: history.formats_for(&id),
                     id: id.clone(),
-                    title: e.title.unwrap_or_else(|| "제목 없음".to_string()),
+                    title: e.title.unwrap_or_else(|| rust_i18n::t!("playlist.untitled").to_string()),
                     url: e.url.unwrap_or_else(|| format!("https://www.youtube.com/watch?v={}", id)),
                     thumbnail: e.thumbnail,
                     duration: e.duration,
                     duration_string: e.duration_string,
+                    uploader: e.uploader.or(e.channel).or_else(|| response_uploader.clone()),
+                    subtitles: Vec::new(),
+                    chapters: Vec::new(),
+                    playlist_index: Some(idx + 1),
                     selected: true,
                 })
             })
             .collect();
 
         Ok(PlaylistInfo {
-            title: response.title.unwrap_or_else(|| "플레이리스트".to_string()),
+            title: response.title.unwrap_or_else(|| rust_i18n::t!("playlist.default_playlist").to_string()),
             entries,
             is_playlist: true,
         })
     } else {
-        // 단일 영상
+        // 단일 영상 (직접 입력한 URL이므로 받은 적이 있어도 선택 유지)
+        let id = response.id.unwrap_or_default();
+        let mut subtitles = subtitle_tracks(response.subtitles, false);
+        subtitles.extend(subtitle_tracks(response.automatic_captions, true));
         let entry = VideoEntry {
-            id: response.id.unwrap_or_default(),
-            title: response.title.clone().unwrap_or_else(|| "제목 없음".to_string()),
+            downloaded_formats: history.formats_for(&id),
+            id,
+            title: response.title.clone().unwrap_or_else(|| rust_i18n::t!("playlist.untitled").to_string()),
             url: response.webpage_url.unwrap_or_else(|| url.to_string()),
             thumbnail: response.thumbnail,
             duration: response.duration,
             duration_string: response.duration_string,
+            uploader: response_uploader,
+            subtitles,
+            chapters: response.chapters.unwrap_or_default(),
+            playlist_index: None,
             selected: true,
         };
 
         Ok(PlaylistInfo {
-            title: response.title.unwrap_or_else(|| "영상".to_string()),
+            title: response.title.unwrap_or_else(|| rust_i18n::t!("playlist.default_video").to_string()),
             entries: vec![entry],
             is_playlist: false,
         })
     }
 }
+
+/// 단일 영상에서 받을 수 있는 스트림 목록 가져오기
+///
+/// `--flat-playlist` 없이 전체 정보를 받아야 하므로 분석보다 느리다.
+/// 스토리보드처럼 영상/소리가 모두 없는 항목은 제외한다.
+pub fn fetch_formats(url: &str, auth: &AuthOptions, proxy: Option<&str>) -> Result<Vec<FormatInfo>, Error> {
+    let response: YtDlpFormatsResponse = run_ytdlp_json(&["--no-playlist", "-J", "--no-warnings", url], auth, proxy)?;
+
+    Ok(response.formats
+        .into_iter()
+        .filter(|f| f.has_video() || f.has_audio())
+        .collect())
+}
@@ -0,0 +1,407 @@
+use crate::downloader::{download_video, DownloadConfig, DownloadProgress, DownloadStatus};
+use crate::error::Error;
+use crate::history::DownloadHistory;
+use crate::playlist::VideoEntry;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::collections::hash_map::RandomState;
+use std::hash::BuildHasher;
+use std::sync::mpsc::{channel, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+/// 큐에 넣을 다운로드 항목
+#[derive(Debug, Clone)]
+pub struct QueueItem {
+    pub id: String, // 다운로드 기록용 영상 id
+    pub title: String,
+    pub config: DownloadConfig,
+}
+
+/// 항목별 진행 상태
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ItemState {
+    Pending,
+    Waiting(u32), // 다운로드 사이 대기 (초)
+    Starting,
+    Downloading(DownloadProgress),
+    Converting,
+    Completed,
+    Failed(Error, Vec<String>), // error, stderr 마지막 줄들
+    Stopped,
+}
+
+impl ItemState {
+    /// 더 이상 진행되지 않는 상태인지
+    pub fn is_finished(&self) -> bool {
+        matches!(self, ItemState::Completed | ItemState::Failed(..) | ItemState::Stopped)
+    }
+
+    /// 현재 작업 중인 상태인지
+    pub fn is_active(&self) -> bool {
+        matches!(self, ItemState::Waiting(_) | ItemState::Starting | ItemState::Downloading(..) | ItemState::Converting)
+    }
+
+    /// 전체 진행률 계산용 완료 비율 (0.0 ~ 1.0)
+    fn fraction(&self) -> f64 {
+        match self {
+            ItemState::Pending | ItemState::Waiting(_) | ItemState::Starting | ItemState::Stopped => 0.0,
+            ItemState::Downloading(p) => (p.percent / 100.0).clamp(0.0, 1.0),
+            ItemState::Converting | ItemState::Completed | ItemState::Failed(..) => 1.0,
+        }
+    }
+
+    fn from_status(status: &DownloadStatus) -> Self {
+        match status {
+            DownloadStatus::Starting(_) => ItemState::Starting,
+            DownloadStatus::Progress(p) => ItemState::Downloading(p.clone()),
+            DownloadStatus::Converting => ItemState::Converting,
+            DownloadStatus::Completed(_) => ItemState::Completed,
+            DownloadStatus::Failed(e, stderr) => ItemState::Failed(e.clone(), stderr.clone()),
+            DownloadStatus::Stopped => ItemState::Stopped,
+        }
+    }
+}
+
+/// 큐 전체 진행 요약
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct QueueProgress {
+    pub total: usize,
+    pub completed: usize,
+    pub failed: usize,
+    pub stopped: usize,
+    pub active: usize,
+    pub fraction: f64, // 0.0 ~ 1.0
+}
+
+impl QueueProgress {
+    pub fn from_states(states: &[ItemState]) -> Self {
+        let total = states.len();
+        let count = |f: fn(&ItemState) -> bool| states.iter().filter(|s| f(s)).count();
+        let fraction = if total > 0 {
+            states.iter().map(ItemState::fraction).sum::<f64>() / total as f64
+        } else {
+            1.0
+        };
+
+        Self {
+            total,
+            completed: count(|s| matches!(s, ItemState::Completed)),
+            failed: count(|s| matches!(s, ItemState::Failed(..))),
+            stopped: count(|s| matches!(s, ItemState::Stopped)),
+            active: count(ItemState::is_active),
+            fraction,
+        }
+    }
+
+    /// 완료/실패/중지된 항목 수
+    pub fn finished(&self) -> usize {
+        self.completed + self.failed + self.stopped
+    }
+}
+
+/// 스케줄러가 UI로 보내는 이벤트
+#[derive(Debug, Clone)]
+pub enum QueueEvent {
+    /// 항목 상태 변경 (index, 새 상태, 전체 요약)
+    Updated(usize, ItemState, QueueProgress),
+    /// 모든 워커 종료
+    Finished(QueueProgress),
+}
+
+struct Shared {
+    states: Vec<ItemState>,
+    next: usize,
+    stopped: bool,
+    active: HashMap<usize, Sender<()>>,
+}
+
+/// 실행 중인 큐 제어용 핸들
+#[derive(Clone)]
+pub struct QueueHandle {
+    shared: Arc<Mutex<Shared>>,
+}
+
+impl QueueHandle {
+    /// 새 항목 시작을 막고 진행 중인 다운로드를 모두 중지
+    pub fn stop(&self) {
+        let mut shared = self.shared.lock().unwrap();
+        shared.stopped = true;
+        for stop_tx in shared.active.values() {
+            let _ = stop_tx.send(());
+        }
+    }
+}
+
+/// `parallelism`개의 워커로 `download_video`를 동시에 실행
+pub fn run_queue(items: Vec<QueueItem>, parallelism: usize, tx: Sender<QueueEvent>) -> QueueHandle {
+    let shared = Arc::new(Mutex::new(Shared {
+        states: vec![ItemState::Pending; items.len()],
+        next: 0,
+        stopped: false,
+        active: HashMap::new(),
+    }));
+    let items = Arc::new(items);
+    let worker_count = parallelism.clamp(1, items.len().max(1));
+
+    let workers: Vec<_> = (0..worker_count)
+        .map(|_| {
+            let shared = shared.clone();
+            let items = items.clone();
+            let tx = tx.clone();
+            thread::spawn(move || worker_loop(&shared, &items, worker_count, &tx))
+        })
+        .collect();
+
+    // 모든 워커가 끝나면 완료 이벤트 전송
+    let shared_for_join = shared.clone();
+    thread::spawn(move || {
+        for worker in workers {
+            let _ = worker.join();
+        }
+        let progress = QueueProgress::from_states(&shared_for_join.lock().unwrap().states);
+        let _ = tx.send(QueueEvent::Finished(progress));
+    });
+
+    QueueHandle { shared }
+}
+
+fn worker_loop(shared: &Mutex<Shared>, items: &[QueueItem], worker_count: usize, tx: &Sender<QueueEvent>) {
+    loop {
+        // 다음 항목 가져오기 (중지 플래그와 같은 잠금 안에서 등록해야 stop()을 놓치지 않음)
+        let (idx, stop_rx) = {
+            let mut s = shared.lock().unwrap();
+            if s.stopped || s.next >= items.len() {
+                return;
+            }
+            let idx = s.next;
+            s.next += 1;
+            let (stop_tx, stop_rx) = channel();
+            s.active.insert(idx, stop_tx);
+            (idx, stop_rx)
+        };
+
+        let mut item = items[idx].clone();
+        // 다운로드 사이 대기는 yt-dlp에 넘기지 않고 여기서 한다 (처음 동시에 시작하는 항목들은 바로 받음)
+        let sleep_interval = item.config.sleep_interval.take();
+        if let Some((min, max)) = sleep_interval.filter(|_| idx >= worker_count) {
+            let secs = random_between(min, max);
+            if secs > 0 {
+                update_state(shared, tx, idx, ItemState::Waiting(secs));
+                // 대기 중 중지하면 yt-dlp를 실행하지 않고 끝냄
+                if stop_rx.recv_timeout(Duration::from_secs(secs.into())) != Err(RecvTimeoutError::Timeout) {
+                    update_state(shared, tx, idx, ItemState::Stopped);
+                    shared.lock().unwrap().active.remove(&idx);
+                    continue;
+                }
+            }
+        }
+
+        let (item_tx, item_rx) = channel();
+        let handle = thread::spawn(move || {
+            download_video(item.config, item.title, item_tx, stop_rx);
+        });
+
+        // 항목 상태 중계
+        for status in item_rx {
+            if let DownloadStatus::Completed(_) = &status {
+                let item = &items[idx];
+                let _ = DownloadHistory::record(&item.id, &item.config.format, &item.title);
+            }
+
+            update_state(shared, tx, idx, ItemState::from_status(&status));
+        }
+
+        let _ = handle.join();
+        shared.lock().unwrap().active.remove(&idx);
+    }
+}
+
+/// 항목 상태를 기록하고 UI로 알림
+fn update_state(shared: &Mutex<Shared>, tx: &Sender<QueueEvent>, idx: usize, state: ItemState) {
+    let progress = {
+        let mut s = shared.lock().unwrap();
+        s.states[idx] = state.clone();
+        QueueProgress::from_states(&s.states)
+    };
+    let _ = tx.send(QueueEvent::Updated(idx, state, progress));
+}
+
+/// `min..=max` 사이 무작위 초 (요청 간격을 흩어놓는 용도라 `RandomState` 해시로 충분)
+fn random_between(min: u32, max: u32) -> u32 {
+    let span = u64::from(max.saturating_sub(min)) + 1;
+    min + (RandomState::new().hash_one(std::time::SystemTime::now()) % span) as u32
+}
+
+/// 앱 재시작 후 이어받기 위해 저장하는 큐 항목
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedQueueItem {
+    pub video: VideoEntry,
+    pub config: DownloadConfig,
+    pub state: ItemState,
+}
+
+/// 저장된 다운로드 큐 (config.toml 옆의 queue.json)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SavedQueue {
+    pub title: String,
+    pub is_playlist: bool,
+    pub items: Vec<SavedQueueItem>,
+}
+
+impl SavedQueue {
+    /// 큐 파일 경로
+    fn queue_path() -> PathBuf {
+        crate::config::config_dir().join("queue.json")
+    }
+
+    /// 저장된 큐 로드 (진행 중이던 항목은 대기 상태로 되돌림)
+    pub fn load() -> Option<Self> {
+        let content = fs::read_to_string(Self::queue_path()).ok()?;
+        let mut queue: SavedQueue = serde_json::from_str(&content).ok()?;
+        for item in &mut queue.items {
+            if item.state.is_active() {
+                item.state = ItemState::Pending;
+            }
+        }
+        Some(queue)
+    }
+
+    /// 큐 저장
+    pub fn save(&self) -> Result<(), Error> {
+        let path = Self::queue_path();
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let content = serde_json::to_string_pretty(self)?;
+        fs::write(&path, content)?;
+
+        Ok(())
+    }
+
+    /// 저장된 큐 삭제
+    pub fn clear() {
+        let _ = fs::remove_file(Self::queue_path());
+    }
+
+    /// 완료되지 않은 항목들
+    pub fn unfinished(&self) -> impl Iterator<Item = &SavedQueueItem> {
+        self.items.iter().filter(|item| item.state != ItemState::Completed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::downloader::DownloadFormat;
+
+    fn downloading(percent: f64) -> ItemState {
+        ItemState::Downloading(DownloadProgress { percent, ..Default::default() })
+    }
+
+    fn item(sleep_interval: Option<(u32, u32)>) -> QueueItem {
+        let mut config = DownloadConfig::new(String::new(), DownloadFormat::Mp3, PathBuf::new());
+        config.sleep_interval = sleep_interval;
+        QueueItem { id: "vid".to_string(), title: "Title".to_string(), config }
+    }
+
+    fn shared(len: usize) -> Arc<Mutex<Shared>> {
+        Arc::new(Mutex::new(Shared {
+            states: vec![ItemState::Pending; len],
+            next: 0,
+            stopped: false,
+            active: HashMap::new(),
+        }))
+    }
+
+    #[test]
+    fn counts_states() {
+        let progress = QueueProgress::from_states(&[
+            ItemState::Completed,
+            ItemState::Failed(Error::RateLimited, Vec::new()),
+            ItemState::Stopped,
+            ItemState::Waiting(5),
+            downloading(50.0),
+            ItemState::Pending,
+        ]);
+
+        assert_eq!(progress.total, 6);
+        assert_eq!((progress.completed, progress.failed, progress.stopped, progress.active), (1, 1, 1, 2));
+        assert_eq!(progress.finished(), 3);
+        assert!((progress.fraction - 2.5 / 6.0).abs() < 1e-9);
+        assert_eq!(QueueProgress::from_states(&[]).fraction, 1.0);
+    }
+
+    #[test]
+    fn classifies_states() {
+        for state in [ItemState::Completed, ItemState::Failed(Error::DiskFull, Vec::new()), ItemState::Stopped] {
+            assert!(state.is_finished() && !state.is_active(), "{:?}", state);
+        }
+        for state in [ItemState::Waiting(1), ItemState::Starting, downloading(10.0), ItemState::Converting] {
+            assert!(state.is_active() && !state.is_finished(), "{:?}", state);
+        }
+        assert!(!ItemState::Pending.is_active() && !ItemState::Pending.is_finished());
+
+        assert_eq!(ItemState::Waiting(30).fraction(), 0.0);
+        assert_eq!(downloading(150.0).fraction(), 1.0);
+        assert_eq!(ItemState::Converting.fraction(), 1.0);
+    }
+
+    #[test]
+    fn maps_download_status() {
+        assert_eq!(ItemState::from_status(&DownloadStatus::Starting("...".to_string())), ItemState::Starting);
+        assert_eq!(ItemState::from_status(&DownloadStatus::Progress(DownloadProgress { percent: 40.0, ..Default::default() })), downloading(40.0));
+        assert_eq!(ItemState::from_status(&DownloadStatus::Completed("a.mp3".to_string())), ItemState::Completed);
+        assert_eq!(
+            ItemState::from_status(&DownloadStatus::Failed(Error::RateLimited, vec!["429".to_string()])),
+            ItemState::Failed(Error::RateLimited, vec!["429".to_string()])
+        );
+        assert_eq!(ItemState::from_status(&DownloadStatus::Stopped), ItemState::Stopped);
+    }
+
+    #[test]
+    fn stop_signals_active_items_and_skips_pending() {
+        let shared = shared(2);
+        let (stop_tx, stop_rx) = channel();
+        shared.lock().unwrap().active.insert(0, stop_tx);
+        QueueHandle { shared: shared.clone() }.stop();
+        assert!(stop_rx.try_recv().is_ok());
+
+        // 중지 후에는 남은 항목을 꺼내지 않음 (yt-dlp도 실행되지 않음)
+        let (tx, rx) = channel();
+        worker_loop(&shared, &[item(None), item(None)], 1, &tx);
+        assert!(rx.try_recv().is_err());
+        let s = shared.lock().unwrap();
+        assert_eq!(s.next, 0);
+        assert_eq!(s.states, [ItemState::Pending, ItemState::Pending]);
+    }
+
+    #[test]
+    fn stop_during_wait_marks_item_stopped() {
+        let shared = shared(1);
+        let handle = QueueHandle { shared: shared.clone() };
+        let (tx, rx) = channel();
+        // 워커 수 0 → 첫 항목부터 대기
+        let worker = {
+            let shared = shared.clone();
+            thread::spawn(move || worker_loop(&shared, &[item(Some((600, 600)))], 0, &tx))
+        };
+
+        assert!(matches!(rx.recv_timeout(Duration::from_secs(5)), Ok(QueueEvent::Updated(0, ItemState::Waiting(600), _))));
+        handle.stop();
+        assert!(matches!(rx.recv_timeout(Duration::from_secs(5)), Ok(QueueEvent::Updated(0, ItemState::Stopped, _))));
+        worker.join().unwrap();
+        assert!(shared.lock().unwrap().active.is_empty());
+    }
+
+    #[test]
+    fn random_between_stays_in_range() {
+        assert_eq!(random_between(3, 3), 3);
+        assert!((5..=30).contains(&random_between(5, 30)));
+    }
+}
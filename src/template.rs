@@ -0,0 +1,208 @@
+use crate::downloader::{sanitize_filename, DownloadFormat};
+use crate::error::Error;
+use crate::playlist::{PlaylistInfo, VideoEntry};
+use std::path::{Component, Path, PathBuf};
+
+/// 기본 출력 파일명 템플릿 (기존 동작과 동일)
+pub const DEFAULT_OUTPUT_TEMPLATE: &str = "{title}.{ext}";
+
+/// 템플릿에서 쓸 수 있는 자리표시자
+pub const PLACEHOLDERS: [&str; 7] = ["title", "id", "uploader", "playlist", "index", "format", "ext"];
+
+/// 출력 파일명 템플릿에 채워 넣을 값
+#[derive(Debug, Clone, Default)]
+pub struct TemplateVars {
+    pub title: String,
+    pub id: String,
+    pub uploader: Option<String>,
+    pub playlist: Option<String>,
+    pub index: Option<usize>, // 플레이리스트 내 위치 (1부터)
+    pub format: String,
+}
+
+impl TemplateVars {
+    /// 영상/플레이리스트 정보로 값 구성 (`playlist`는 플레이리스트일 때만 넘긴다)
+    pub fn new(entry: &VideoEntry, playlist: Option<&PlaylistInfo>, format: &DownloadFormat) -> Self {
+        Self {
+            title: entry.title.clone(),
+            id: entry.id.clone(),
+            uploader: entry.uploader.clone(),
+            playlist: playlist.map(|info| info.title.clone()),
+            index: playlist.and(entry.playlist_index),
+            format: crate::config::AppConfig::format_to_string(format),
+        }
+    }
+}
+
+/// 플레이리스트용 템플릿: 플레이리스트 제목 폴더 + 파일명 앞 트랙 번호
+///
+/// 번호 자릿수는 항목 수에 맞춘다 (99개 이하면 "01", 100개 이상이면 "001").
+/// 템플릿에 이미 `{playlist}`/`{index}`가 있으면 해당 부분은 덧붙이지 않는다.
+pub fn playlist_template(template: &str, entry_count: usize) -> String {
+    let (dir, file) = match template.rfind(['/', '\\']) {
+        Some(pos) => (&template[..=pos], &template[pos + 1..]),
+        None => ("", template),
+    };
+
+    let folder = if template.contains("{playlist") { "" } else { "{playlist}/" };
+    let prefix = if template.contains("{index") {
+        String::new()
+    } else {
+        let width = entry_count.max(1).to_string().len().max(2);
+        format!("{{index:{:02}}} - ", width)
+    };
+
+    format!("{}{}{}{}", folder, dir, prefix, file)
+}
+
+/// 템플릿 확장 → `output_dir` 기준 상대 경로
+///
+/// `{index:03}`처럼 너비를 주면 0으로 채운다. `{ext}`는 `ext` 인자로 그대로 바뀌므로
+/// yt-dlp에 넘길 때는 `"%(ext)s"`, 미리보기에는 실제 확장자를 넘긴다.
+/// 값이 없는 자리표시자가 만든 빈 폴더 단계는 건너뛴다.
+pub fn expand_template(template: &str, vars: &TemplateVars, ext: &str) -> Result<PathBuf, Error> {
+    if template.trim().is_empty() {
+        return Err(Error::InvalidTemplate(template.to_string()));
+    }
+    // 절대 경로와 드라이브 지정("C:")은 저장 폴더 밖으로 나가므로 거부
+    if template.starts_with(['/', '\\']) || has_drive_prefix(template) {
+        return Err(Error::InvalidTemplate(template.to_string()));
+    }
+
+    let mut expanded = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        push_literal(&mut expanded, &rest[..start]);
+        let after = &rest[start + 1..];
+        let end = after.find('}').ok_or_else(|| Error::InvalidTemplate(template.to_string()))?;
+        expanded.push_str(&expand_placeholder(&after[..end], vars, ext).ok_or_else(|| Error::InvalidTemplate(format!("{{{}}}", &after[..end])))?);
+        rest = &after[end + 1..];
+    }
+    if rest.contains('}') {
+        return Err(Error::InvalidTemplate(template.to_string()));
+    }
+    push_literal(&mut expanded, rest);
+
+    let mut path = PathBuf::new();
+    for part in expanded.split(['/', '\\']).map(str::trim).filter(|p| !p.is_empty()) {
+        // ".", "..", 드라이브/루트로 해석되는 단계는 거부
+        if !Path::new(part).components().all(|c| matches!(c, Component::Normal(_))) {
+            return Err(Error::InvalidTemplate(template.to_string()));
+        }
+        path.push(part);
+    }
+
+    // 파일명 단계가 확장자만 남았으면 의미 없는 경로
+    if path.file_name().is_none_or(|name| name.to_string_lossy().trim_start_matches('.') == ext.trim_start_matches('.')) {
+        return Err(Error::InvalidTemplate(template.to_string()));
+    }
+
+    Ok(path)
+}
+
+/// 저장 폴더와 상대 경로를 합치고, 결과가 저장 폴더 안에 있는지 확인
+pub fn resolve_output_path(output_dir: &Path, relative: &Path) -> Result<PathBuf, Error> {
+    let inside = relative.components().all(|c| matches!(c, Component::Normal(_)));
+    if !inside || relative.as_os_str().is_empty() {
+        return Err(Error::InvalidTemplate(relative.display().to_string()));
+    }
+    Ok(output_dir.join(relative))
+}
+
+/// 템플릿의 일반 글자 (yt-dlp 템플릿 문법과 겹치지 않도록 `%` 이스케이프)
+///
+/// Windows에서는 파일명에 쓸 수 없는 ':'를 자리표시자 값처럼 뺀다.
+fn push_literal(out: &mut String, literal: &str) {
+    #[cfg(windows)]
+    let literal = &literal.replace(':', "");
+    out.push_str(&literal.replace('%', "%%"));
+}
+
+/// "C:"처럼 드라이브 문자로 시작하는지
+fn has_drive_prefix(template: &str) -> bool {
+    let bytes = template.trim_start().as_bytes();
+    bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':'
+}
+
+fn expand_placeholder(spec: &str, vars: &TemplateVars, ext: &str) -> Option<String> {
+    let (name, width) = match spec.split_once(':') {
+        Some((name, width)) => (name.trim(), Some(width.trim().parse::<usize>().ok()?)),
+        None => (spec.trim(), None),
+    };
+
+    let value = match name {
+        "title" => sanitize_filename(&vars.title),
+        "id" => sanitize_filename(&vars.id),
+        "uploader" => vars.uploader.as_deref().map(sanitize_filename).unwrap_or_default(),
+        "playlist" => vars.playlist.as_deref().map(sanitize_filename).unwrap_or_default(),
+        "index" => match vars.index {
+            Some(index) => format!("{:0width$}", index, width = width.unwrap_or(0)),
+            None => String::new(),
+        },
+        "format" => vars.format.clone(),
+        "ext" => ext.to_string(),
+        _ => return None,
+    };
+    Some(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars() -> TemplateVars {
+        TemplateVars {
+            title: "Song: Part 1/2".to_string(),
+            id: "abc123".to_string(),
+            uploader: Some("Some Artist".to_string()),
+            playlist: Some("Best Of".to_string()),
+            index: Some(7),
+            format: "mp3".to_string(),
+        }
+    }
+
+    #[test]
+    fn expands_library_layout() {
+        let path = expand_template("{uploader}/{playlist}/{index:03} - {title}.{ext}", &vars(), "%(ext)s").unwrap();
+        assert_eq!(path, PathBuf::from("Some Artist").join("Best Of").join("007 - Song Part 12.%(ext)s"));
+    }
+
+    #[test]
+    fn skips_empty_folders_for_single_videos() {
+        let single = TemplateVars { playlist: None, index: None, ..vars() };
+        let path = expand_template("{playlist}/{title} [{id}].{ext}", &single, "mp3").unwrap();
+        assert_eq!(path, PathBuf::from("Song Part 12 [abc123].mp3"));
+    }
+
+    #[test]
+    fn escapes_percent_in_literals() {
+        let path = expand_template("100% {title}.{ext}", &vars(), "%(ext)s").unwrap();
+        assert_eq!(path, PathBuf::from("100%% Song Part 12.%(ext)s"));
+    }
+
+    #[test]
+    fn playlist_template_adds_folder_and_track_number() {
+        assert_eq!(playlist_template("{title}.{ext}", 12), "{playlist}/{index:02} - {title}.{ext}");
+        assert_eq!(playlist_template("{uploader}/{title}.{ext}", 150), "{playlist}/{uploader}/{index:03} - {title}.{ext}");
+        assert_eq!(playlist_template("{playlist}/{title}.{ext}", 5), "{playlist}/{index:02} - {title}.{ext}");
+        assert_eq!(playlist_template("{index} {title}.{ext}", 5), "{playlist}/{index} {title}.{ext}");
+
+        let path = expand_template(&playlist_template("{title}.{ext}", 12), &vars(), "%(ext)s").unwrap();
+        assert_eq!(path, PathBuf::from("Best Of").join("07 - Song Part 12.%(ext)s"));
+    }
+
+    #[test]
+    fn keeps_colons_in_literal_text() {
+        let expected = if cfg!(windows) { "Live 2024/Song Part 12.mp3" } else { "Live: 2024/Song Part 12.mp3" };
+        assert_eq!(expand_template("Live: 2024/{title}.{ext}", &vars(), "mp3").unwrap(), PathBuf::from(expected));
+    }
+
+    #[test]
+    fn rejects_paths_outside_download_dir() {
+        for template in ["../{title}.{ext}", "/tmp/{title}.{ext}", "C:/{title}.{ext}", "d:{title}.{ext}", "a/./{title}.{ext}", "{unknown}.{ext}", "{title", "{ext}", ""] {
+            assert!(matches!(expand_template(template, &vars(), "mp3"), Err(Error::InvalidTemplate(_))), "{}", template);
+        }
+        assert!(resolve_output_path(Path::new("/music"), Path::new("../x.mp3")).is_err());
+        assert_eq!(resolve_output_path(Path::new("/music"), Path::new("a/x.mp3")).unwrap(), PathBuf::from("/music/a/x.mp3"));
+    }
+}
@@ -0,0 +1,107 @@
+//! 프록시 설정을 따르는 썸네일 로더
+//!
+//! egui_extras의 기본 http 로더는 프록시를 쓸 수 없으므로 같은 주소를 먼저 가로채
+//! `rust_yt::network::http_client`로 받는다.
+
+use eframe::egui;
+use egui::load::{Bytes, BytesLoadResult, BytesLoader, BytesPoll, LoadError};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::task::Poll;
+use std::thread;
+use std::time::Duration;
+
+type Entry = Poll<Result<(Arc<[u8]>, Option<String>), String>>;
+
+pub struct ThumbnailLoader {
+    proxy: Mutex<Option<String>>,
+    cache: Arc<Mutex<HashMap<String, Entry>>>,
+}
+
+impl ThumbnailLoader {
+    pub const ID: &'static str = egui::generate_loader_id!(ThumbnailLoader);
+
+    pub fn new(proxy: Option<String>) -> Self {
+        Self {
+            proxy: Mutex::new(proxy),
+            cache: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// 프록시 변경 (실패한 썸네일은 새 설정으로 다시 받음)
+    pub fn set_proxy(&self, proxy: Option<String>) {
+        *self.proxy.lock().unwrap() = proxy;
+        self.cache.lock().unwrap().retain(|_, entry| !matches!(entry, Poll::Ready(Err(_))));
+    }
+}
+
+fn fetch(uri: &str, proxy: Option<&str>) -> Result<(Arc<[u8]>, Option<String>), String> {
+    let client = rust_yt::network::http_client(proxy, Duration::from_secs(30)).map_err(|e| e.to_string())?;
+    let response = client.get(uri)
+        .send()
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| e.to_string())?;
+    let mime = response.headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string);
+    let bytes = response.bytes().map_err(|e| e.to_string())?;
+    Ok((bytes.to_vec().into(), mime))
+}
+
+impl BytesLoader for ThumbnailLoader {
+    fn id(&self) -> &str {
+        Self::ID
+    }
+
+    fn load(&self, ctx: &egui::Context, uri: &str) -> BytesLoadResult {
+        if !uri.starts_with("http://") && !uri.starts_with("https://") {
+            return Err(LoadError::NotSupported);
+        }
+
+        let mut cache = self.cache.lock().unwrap();
+        match cache.get(uri) {
+            Some(Poll::Ready(Ok((bytes, mime)))) => Ok(BytesPoll::Ready {
+                size: None,
+                bytes: Bytes::Shared(bytes.clone()),
+                mime: mime.clone(),
+            }),
+            Some(Poll::Ready(Err(err))) => Err(LoadError::Loading(err.clone())),
+            Some(Poll::Pending) => Ok(BytesPoll::Pending { size: None }),
+            None => {
+                cache.insert(uri.to_string(), Poll::Pending);
+                drop(cache);
+
+                let uri = uri.to_string();
+                let proxy = self.proxy.lock().unwrap().clone();
+                let cache = self.cache.clone();
+                let ctx = ctx.clone();
+                thread::spawn(move || {
+                    let result = fetch(&uri, proxy.as_deref());
+                    cache.lock().unwrap().insert(uri, Poll::Ready(result));
+                    ctx.request_repaint();
+                });
+                Ok(BytesPoll::Pending { size: None })
+            }
+        }
+    }
+
+    fn forget(&self, uri: &str) {
+        self.cache.lock().unwrap().remove(uri);
+    }
+
+    fn forget_all(&self) {
+        self.cache.lock().unwrap().clear();
+    }
+
+    fn byte_size(&self) -> usize {
+        self.cache.lock().unwrap()
+            .values()
+            .map(|entry| match entry {
+                Poll::Ready(Ok((bytes, mime))) => bytes.len() + mime.as_ref().map_or(0, |m| m.len()),
+                Poll::Ready(Err(err)) => err.len(),
+                Poll::Pending => 0,
+            })
+            .sum()
+    }
+}
@@ -0,0 +1,264 @@
+//! 가짜 yt-dlp 실행 파일로 라이브러리를 검증하는 통합 테스트 하네스
+//!
+//! 스크립트는 마지막 인자(URL)의 `fake://<시나리오>` 값에 따라 정해진 출력을 내보내고,
+//! 받은 인자를 `args-<시나리오>` 파일에 기록한다.
+
+#![allow(dead_code)]
+
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::thread;
+
+const FAKE_YTDLP: &str = r#"#!/bin/sh
+dir="$(dirname "$0")"
+url=""
+json=0
+for arg in "$@"; do
+    [ "$arg" = "-J" ] && json=1
+    url="$arg"
+done
+scenario="${url#fake://}"
+printf '%s\n' "$@" > "$dir/args-$scenario"
+
+if [ "$json" = 1 ]; then
+    case "$scenario" in
+        single)
+            echo '{"_type": "video", "id": "vid001", "title": "Single Video", "webpage_url": "https://www.youtube.com/watch?v=vid001", "thumbnail": "https://i.ytimg.com/vi/vid001/hq.jpg", "duration": 125.0, "duration_string": "2:05", "uploader": "Test Channel",
+                "subtitles": {"ko": [{"ext": "vtt", "name": "Korean"}], "en": [{"ext": "json3"}, {"ext": "vtt", "name": "English"}], "live_chat": [{"ext": "json"}]},
+                "automatic_captions": {"en": [{"ext": "vtt", "name": "English (auto)"}]},
+                "chapters": [{"start_time": 0.0, "end_time": 12.5, "title": "Intro"}, {"start_time": 12.5, "end_time": 125.0, "title": "Main"}]}'
+            ;;
+        single-no-id)
+            echo '{"_type": "video", "title": "No Id Video", "duration": 61.0}'
+            ;;
+        playlist)
+            echo '{"_type": "playlist", "id": "PL1", "title": "Test Playlist", "channel": "Playlist Owner", "entries": [
+                {"id": "pl001", "title": "First", "url": "https://www.youtube.com/watch?v=pl001", "duration": 30.0, "channel": "Entry Channel"},
+                {"title": "Missing Id", "url": "https://www.youtube.com/watch?v=broken"},
+                {"id": "pl003", "duration": 3725.0}
+            ]}'
+            ;;
+        unavailable)
+            echo 'ERROR: [youtube] gone: Video unavailable. This video has been removed by the uploader' >&2
+            exit 1
+            ;;
+        signin)
+            echo "ERROR: [youtube] age: Sign in to confirm your age. This video may be inappropriate for some users. Use --cookies-from-browser or --cookies for the authentication." >&2
+            exit 1
+            ;;
+        formats)
+            echo '{"_type": "video", "id": "vid002", "title": "Formats Video", "formats": [
+                {"format_id": "sb0", "ext": "mhtml", "format_note": "storyboard", "vcodec": "none", "acodec": "none"},
+                {"format_id": "140", "ext": "m4a", "format_note": "medium", "vcodec": "none", "acodec": "mp4a.40.2", "tbr": 129.5, "filesize": 2048000, "language": "en"},
+                {"format_id": "18", "ext": "mp4", "width": 640, "height": 360, "fps": 30, "vcodec": "avc1.42001E", "acodec": "mp4a.40.2", "tbr": 500.0, "filesize_approx": 7340032},
+                {"format_id": "299", "ext": "mp4", "width": 1920, "height": 1080, "fps": 60, "vcodec": "avc1.64002a", "acodec": "none", "tbr": 4500.25}
+            ]}'
+            ;;
+        garbage)
+            echo 'this is not json'
+            ;;
+        *)
+            echo "ERROR: Unsupported URL: $url" >&2
+            exit 1
+            ;;
+    esac
+    exit 0
+fi
+
+progress() {
+    echo "[rust-yt-progress] {\"status\": \"downloading\", \"downloaded_bytes\": $1, \"total_bytes\": 1000, \"speed\": 500.0, \"eta\": 1}"
+}
+
+case "$scenario" in
+    ok-audio*)
+        echo '[youtube] ok: Downloading webpage'
+        progress 250
+        progress 1000
+        echo '[rust-yt-progress] {"status": "finished", "downloaded_bytes": 1000, "total_bytes": 1000}'
+        echo '[ExtractAudio] Destination: ok.mp3'
+        ;;
+    ok-video*)
+        progress 500
+        progress 1000
+        echo '[Merger] Merging formats into "ok.mp4"'
+        ;;
+    fail)
+        progress 100
+        echo 'WARNING: [youtube] fail: some formats are missing' >&2
+        echo 'ERROR: unable to download video data: HTTP Error 429: Too Many Requests' >&2
+        exit 1
+        ;;
+    slow)
+        progress 10
+        exec sleep 30
+        ;;
+    *)
+        echo "ERROR: Unsupported URL: $url" >&2
+        exit 2
+        ;;
+esac
+exit 0
+"#;
+
+/// 가짜 yt-dlp가 들어있는 폴더
+pub fn fake_dir() -> &'static PathBuf {
+    static DIR: OnceLock<PathBuf> = OnceLock::new();
+    DIR.get_or_init(|| {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join(format!("rust-yt-fake-{}", std::process::id()));
+        fs::create_dir_all(dir.join("config")).unwrap();
+
+        let script = dir.join("yt-dlp");
+        fs::write(&script, FAKE_YTDLP).unwrap();
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+        // ffmpeg는 yt-dlp에 경로만 넘기므로 실행 가능한 빈 스크립트면 충분
+        let ffmpeg = dir.join("ffmpeg");
+        fs::write(&ffmpeg, "#!/bin/sh\nexit 0\n").unwrap();
+        fs::set_permissions(&ffmpeg, fs::Permissions::from_mode(0o755)).unwrap();
+
+        // SAFETY: OnceLock 초기화 안에서 한 번만 설정하며, 모든 테스트는
+        // yt-dlp를 실행하기 전에 setup()을 먼저 호출한다.
+        unsafe {
+            std::env::set_var(rust_yt::binaries::YTDLP_PATH_ENV, &script);
+            std::env::set_var(rust_yt::binaries::FFMPEG_PATH_ENV, &ffmpeg);
+            // 실제 사용자 다운로드 기록/설정을 읽지 않도록 분리
+            std::env::set_var(rust_yt::config::CONFIG_DIR_ENV, dir.join("config"));
+        }
+        dir
+    })
+}
+
+/// 가짜 yt-dlp를 사용하도록 설정
+pub fn setup() {
+    fake_dir();
+}
+
+/// 시나리오 URL
+pub fn url(scenario: &str) -> String {
+    format!("fake://{}", scenario)
+}
+
+/// 해당 시나리오 실행 시 yt-dlp가 받은 인자
+pub fn recorded_args(scenario: &str) -> Vec<String> {
+    fs::read_to_string(fake_dir().join(format!("args-{}", scenario)))
+        .unwrap_or_default()
+        .lines()
+        .map(str::to_string)
+        .collect()
+}
+
+/// 테스트별 출력 폴더
+pub fn output_dir(name: &str) -> PathBuf {
+    let dir = fake_dir().join("out").join(name);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// 테스트 서버가 모든 응답에 붙이는 ETag
+pub const ETAG: &str = "\"rust-yt-test\"";
+
+/// 서버가 받은 요청 (경로, Range 헤더)
+pub type RequestLog = Arc<Mutex<Vec<(String, Option<String>)>>>;
+
+/// 경로별로 정해진 내용을 돌려주는 로컬 HTTP 서버 (기본 주소 반환, 없는 경로는 404)
+pub fn serve(routes: Vec<(&'static str, Vec<u8>)>) -> String {
+    serve_with(routes, None).0
+}
+
+/// `serve`와 같지만 받은 요청을 기록한다.
+///
+/// `Range: bytes=<시작>-` 요청에는 206으로 나머지를 보내고 (`If-Range`가 `ETAG`와 다르면 200으로 전체),
+/// `interrupt`가 `(경로, n)`이면
+/// 그 경로의 첫 응답은 전체 길이를 알린 뒤 n바이트만 보내고 연결을 끊는다.
+pub fn serve_with(routes: Vec<(&'static str, Vec<u8>)>, interrupt: Option<(&'static str, usize)>) -> (String, RequestLog) {
+    serve_inner(routes, interrupt, None)
+}
+
+/// `serve`와 같지만 `(경로, n)` 경로의 처음 n번 요청에는 503으로 답한다 (받은 요청도 기록)
+pub fn serve_unavailable(routes: Vec<(&'static str, Vec<u8>)>, unavailable: (&'static str, usize)) -> (String, RequestLog) {
+    serve_inner(routes, None, Some(unavailable))
+}
+
+fn serve_inner(
+    routes: Vec<(&'static str, Vec<u8>)>,
+    interrupt: Option<(&'static str, usize)>,
+    unavailable: Option<(&'static str, usize)>,
+) -> (String, RequestLog) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    let log = RequestLog::default();
+    let requests = log.clone();
+
+    thread::spawn(move || {
+        let mut interrupt = interrupt;
+        let mut unavailable = unavailable;
+        for stream in listener.incoming().map_while(Result::ok) {
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut request_line = String::new();
+            if reader.read_line(&mut request_line).is_err() {
+                continue;
+            }
+            let mut range = None;
+            let mut if_range = None;
+            let mut line = String::new();
+            while reader.read_line(&mut line).map(|n| n > 2).unwrap_or(false) {
+                if let Some((name, value)) = line.split_once(':') {
+                    if name.eq_ignore_ascii_case("range") {
+                        range = Some(value.trim().to_string());
+                    } else if name.eq_ignore_ascii_case("if-range") {
+                        if_range = Some(value.trim().to_string());
+                    }
+                }
+                line.clear();
+            }
+
+            let path = request_line.split_whitespace().nth(1).unwrap_or("").to_string();
+            requests.lock().unwrap().push((path.clone(), range.clone()));
+            let start = range.as_deref()
+                .and_then(|r| r.strip_prefix("bytes=")?.strip_suffix('-')?.parse::<usize>().ok())
+                .filter(|_| if_range.as_deref().is_none_or(|v| v == ETAG));
+
+            let mut stream = stream;
+            if let Some((_, left)) = unavailable.as_mut().filter(|(route, left)| *route == path && *left > 0) {
+                *left -= 1;
+                let _ = write!(stream, "HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\nConnection: close\r\n\r\n");
+                continue;
+            }
+            match routes.iter().find(|(route, _)| *route == path) {
+                Some((_, body)) if start.is_some_and(|start| start >= body.len()) => {
+                    let _ = write!(stream, "HTTP/1.1 416 Range Not Satisfiable\r\nContent-Length: 0\r\nConnection: close\r\n\r\n");
+                }
+                Some((_, body)) => {
+                    match start {
+                        Some(start) => {
+                            let _ = write!(stream, "HTTP/1.1 206 Partial Content\r\nContent-Range: bytes {}-{}/{}\r\nContent-Length: {}\r\nETag: {}\r\nConnection: close\r\n\r\n",
+                                start, body.len() - 1, body.len(), body.len() - start, ETAG);
+                        }
+                        None => {
+                            let _ = write!(stream, "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nETag: {}\r\nConnection: close\r\n\r\n", body.len(), ETAG);
+                        }
+                    }
+                    let body = &body[start.unwrap_or(0)..];
+                    match interrupt.take_if(|(route, _)| *route == path) {
+                        Some((_, sent)) => {
+                            let _ = stream.write_all(&body[..sent]);
+                            let _ = stream.shutdown(std::net::Shutdown::Both);
+                        }
+                        None => {
+                            let _ = stream.write_all(body);
+                        }
+                    }
+                }
+                None => {
+                    let _ = write!(stream, "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n");
+                }
+            }
+        }
+    });
+
+    (base, log)
+}
@@ -0,0 +1,290 @@
+#![cfg(unix)]
+
+mod common;
+
+use rust_yt::downloader::{download_video, DownloadConfig, DownloadFormat, DownloadStatus, SponsorBlockOptions, SponsorCategory, SubtitleFormat, SubtitleOptions, TimeRange, VideoCodec};
+use rust_yt::Error;
+use std::path::PathBuf;
+use std::sync::mpsc::{channel, Sender};
+use std::thread;
+use std::time::Duration;
+
+fn config(scenario: &str, format: DownloadFormat) -> DownloadConfig {
+    DownloadConfig::new(common::url(scenario), format, common::output_dir(scenario))
+}
+
+/// 다운로드를 끝까지 실행하고 받은 상태 목록 반환
+fn run(config: DownloadConfig) -> Vec<DownloadStatus> {
+    let (tx, rx) = channel();
+    let (_stop_tx, stop_rx) = channel::<()>();
+    download_video(config, "Title".to_string(), tx, stop_rx);
+    rx.try_iter().collect()
+}
+
+#[test]
+fn audio_download_reports_progress_conversion_and_completion() {
+    common::setup();
+    let statuses = run(config("ok-audio", DownloadFormat::Mp3));
+
+    assert!(matches!(statuses.first(), Some(DownloadStatus::Starting(_))));
+    let percents: Vec<f64> = statuses.iter()
+        .filter_map(|s| match s {
+            DownloadStatus::Progress(p) => Some(p.percent),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(percents, [25.0, 100.0, 100.0]);
+    assert!(statuses.iter().any(|s| matches!(s, DownloadStatus::Converting)));
+    assert!(matches!(statuses.last(), Some(DownloadStatus::Completed(t)) if t == "Title"));
+
+    let args = common::recorded_args("ok-audio");
+    let pos = args.iter().position(|a| a == "--audio-format").unwrap();
+    assert_eq!(args[pos + 1], "mp3");
+    let pos = args.iter().position(|a| a == "--audio-quality").unwrap();
+    assert_eq!(args[pos + 1], "320K");
+    // 찾은 ffmpeg 경로를 PATH 대신 직접 넘김
+    let pos = args.iter().position(|a| a == "--ffmpeg-location").unwrap();
+    assert_eq!(args[pos + 1], common::fake_dir().join("ffmpeg").to_string_lossy());
+    assert_eq!(args.last().map(String::as_str), Some("fake://ok-audio"));
+}
+
+#[test]
+fn m4a_download_passes_audio_quality() {
+    common::setup();
+    let mut config = config("ok-audio-m4a", DownloadFormat::M4a);
+    config.audio_quality = "2".to_string();
+    let statuses = run(config);
+    assert!(matches!(statuses.last(), Some(DownloadStatus::Completed(_))));
+
+    let args = common::recorded_args("ok-audio-m4a");
+    let pos = args.iter().position(|a| a == "--audio-quality").unwrap();
+    assert_eq!(args[pos + 1], "2");
+    assert!(!args.iter().any(|a| a == "--postprocessor-args"));
+}
+
+#[test]
+fn lossless_download_passes_sample_rate_and_bit_depth() {
+    common::setup();
+    let mut config = config("ok-audio-wav", DownloadFormat::Wav);
+    config.sample_rate = Some(48_000);
+    config.bit_depth = Some(24);
+    let statuses = run(config);
+    assert!(matches!(statuses.last(), Some(DownloadStatus::Completed(_))));
+
+    let args = common::recorded_args("ok-audio-wav");
+    assert!(!args.iter().any(|a| a == "--audio-quality"));
+    let pos = args.iter().position(|a| a == "--postprocessor-args").unwrap();
+    assert_eq!(args[pos + 1], "ExtractAudio:-ar 48000 -c:a pcm_s24le");
+}
+
+#[test]
+fn video_download_reports_merge() {
+    common::setup();
+    let statuses = run(config("ok-video", DownloadFormat::Mp4));
+
+    assert!(statuses.iter().any(|s| matches!(s, DownloadStatus::Converting)));
+    assert!(matches!(statuses.last(), Some(DownloadStatus::Completed(_))));
+
+    let args = common::recorded_args("ok-video");
+    let pos = args.iter().position(|a| a == "--merge-output-format").unwrap();
+    assert_eq!(args[pos + 1], "mp4");
+}
+
+#[test]
+fn video_limits_are_passed_as_format_selector() {
+    common::setup();
+    let mut config = config("ok-video-720", DownloadFormat::Webm);
+    config.max_height = Some(720);
+    config.video_codec = VideoCodec::Vp9;
+    let statuses = run(config);
+    assert!(matches!(statuses.last(), Some(DownloadStatus::Completed(_))));
+
+    let args = common::recorded_args("ok-video-720");
+    let pos = args.iter().position(|a| a == "-f").unwrap();
+    assert!(args[pos + 1].starts_with("bestvideo[height<=720][vcodec^=vp09]+bestaudio/"), "{}", args[pos + 1]);
+    let pos = args.iter().position(|a| a == "--merge-output-format").unwrap();
+    assert_eq!(args[pos + 1], "webm");
+}
+
+#[test]
+fn chosen_format_id_overrides_selector() {
+    common::setup();
+    let mut config = config("ok-video-itag", DownloadFormat::Mp4);
+    config.max_height = Some(480);
+    config.format_id = Some("299+140".to_string());
+    run(config);
+
+    let args = common::recorded_args("ok-video-itag");
+    let pos = args.iter().position(|a| a == "-f").unwrap();
+    assert_eq!(args[pos + 1], "299+140");
+    assert_eq!(args.iter().filter(|a| *a == "-f").count(), 1);
+}
+
+#[test]
+fn output_name_is_joined_to_output_dir() {
+    common::setup();
+    let mut config = config("ok-audio-template", DownloadFormat::Mp3);
+    let output_dir = config.output_dir.clone();
+    config.output_name = Some(PathBuf::from("Artist").join("01 - Title.%(ext)s"));
+    run(config);
+
+    let args = common::recorded_args("ok-audio-template");
+    let pos = args.iter().position(|a| a == "-o").unwrap();
+    assert_eq!(PathBuf::from(&args[pos + 1]), output_dir.join("Artist").join("01 - Title.%(ext)s"));
+}
+
+#[test]
+fn output_name_outside_output_dir_is_rejected() {
+    common::setup();
+    let mut config = config("ok-audio-escape", DownloadFormat::Mp3);
+    config.output_name = Some(PathBuf::from("../escape.%(ext)s"));
+    let statuses = run(config);
+
+    assert!(matches!(statuses.last(), Some(DownloadStatus::Failed(Error::InvalidTemplate(_), _))), "{:?}", statuses);
+    // yt-dlp는 실행되지 않아야 함
+    assert!(common::recorded_args("ok-audio-escape").is_empty());
+}
+
+#[test]
+fn subtitles_are_requested_and_embedded() {
+    common::setup();
+    let mut config = config("ok-video-subs", DownloadFormat::Mp4);
+    config.subtitles = SubtitleOptions {
+        enabled: true,
+        languages: vec!["ko".to_string()],
+        include_auto: false,
+        format: SubtitleFormat::Srt,
+        embed: true,
+    };
+    run(config);
+
+    let args = common::recorded_args("ok-video-subs");
+    assert!(args.contains(&"--write-subs".to_string()));
+    assert!(args.contains(&"--embed-subs".to_string()));
+    assert!(!args.contains(&"--write-auto-subs".to_string()));
+    let pos = args.iter().position(|a| a == "--sub-langs").unwrap();
+    assert_eq!(args[pos + 1], "ko");
+    assert_eq!(args.last().map(String::as_str), Some("fake://ok-video-subs"));
+}
+
+#[test]
+fn split_chapters_writes_chapter_files_next_to_output() {
+    common::setup();
+    let mut config = config("ok-audio-chapters", DownloadFormat::Mp3);
+    let output_dir = config.output_dir.clone();
+    config.output_name = Some(PathBuf::from("Mix.%(ext)s"));
+    config.split_chapters = true;
+    config.skipped_chapters = vec![(0.0, 12.5)];
+    run(config);
+
+    let args = common::recorded_args("ok-audio-chapters");
+    assert!(args.contains(&"--split-chapters".to_string()));
+    let chapter_output = args.iter().find_map(|a| a.strip_prefix("chapter:")).unwrap();
+    assert_eq!(
+        PathBuf::from(chapter_output),
+        output_dir.join("Mix").join("%(section_number)02d - %(section_title)s.%(ext)s")
+    );
+    let pos = args.iter().position(|a| a == "--remove-chapters").unwrap();
+    assert_eq!(args[pos + 1], "*0-12.5");
+}
+
+#[test]
+fn time_range_is_passed_as_download_section() {
+    common::setup();
+    let mut config = config("ok-video-clip", DownloadFormat::Mp4);
+    config.time_range = TimeRange::parse("1:00:00", "1:00:30", Some(10_800.0)).unwrap();
+    config.precise_cuts = true;
+    run(config);
+
+    let args = common::recorded_args("ok-video-clip");
+    let pos = args.iter().position(|a| a == "--download-sections").unwrap();
+    assert_eq!(args[pos + 1], "*3600-3630");
+    assert!(args.contains(&"--force-keyframes-at-cuts".to_string()));
+}
+
+#[test]
+fn rate_limit_is_passed_and_sleep_is_left_to_the_queue() {
+    common::setup();
+    let mut config = config("ok-audio-throttle", DownloadFormat::Mp3);
+    config.rate_limit = Some(2048);
+    config.sleep_interval = Some((5, 30));
+    run(config);
+
+    // 대기는 큐 스케줄러 몫이라 단일 다운로드는 yt-dlp에 대기 인자를 넘기지 않음
+    let args = common::recorded_args("ok-audio-throttle");
+    let pos = args.iter().position(|a| a == "-r").unwrap();
+    assert_eq!(args[pos + 1], "2048K");
+    assert!(!args.iter().any(|a| a.contains("sleep-interval")));
+}
+
+#[test]
+fn sponsorblock_categories_are_passed() {
+    common::setup();
+    let mut config = config("ok-audio-sponsorblock", DownloadFormat::Mp3);
+    config.sponsorblock = SponsorBlockOptions {
+        remove: vec![SponsorCategory::Sponsor, SponsorCategory::Selfpromo],
+        mark: vec![SponsorCategory::Intro],
+    };
+    run(config);
+
+    let args = common::recorded_args("ok-audio-sponsorblock");
+    let pos = args.iter().position(|a| a == "--sponsorblock-remove").unwrap();
+    assert_eq!(args[pos + 1], "sponsor,selfpromo");
+    let pos = args.iter().position(|a| a == "--sponsorblock-mark").unwrap();
+    assert_eq!(args[pos + 1], "intro");
+}
+
+#[test]
+fn cookies_and_proxy_are_passed_to_download() {
+    common::setup();
+    let mut config = config("ok-audio-cookies", DownloadFormat::Mp3);
+    config.auth.cookies_file = Some(PathBuf::from("/tmp/cookies.txt"));
+    config.proxy = Some("http://10.0.0.1:3128".to_string());
+    run(config);
+
+    let args = common::recorded_args("ok-audio-cookies");
+    let pos = args.iter().position(|a| a == "--cookies").unwrap();
+    assert_eq!(args[pos + 1], "/tmp/cookies.txt");
+    assert!(!args.contains(&"--cookies-from-browser".to_string()));
+    let pos = args.iter().position(|a| a == "--proxy").unwrap();
+    assert_eq!(args[pos + 1], "http://10.0.0.1:3128");
+}
+
+#[test]
+fn failed_download_carries_classified_error_and_stderr() {
+    common::setup();
+    let statuses = run(config("fail", DownloadFormat::Mp3));
+
+    match statuses.last() {
+        Some(DownloadStatus::Failed(error, stderr)) => {
+            assert_eq!(error, &Error::RateLimited);
+            assert_eq!(stderr.len(), 2);
+            assert!(stderr[0].starts_with("WARNING:"));
+        }
+        other => panic!("expected failure, got {:?}", other),
+    }
+}
+
+#[test]
+fn stop_signal_kills_running_download() {
+    common::setup();
+    let (tx, rx) = channel();
+    let (stop_tx, stop_rx): (Sender<()>, _) = channel();
+
+    let handle = thread::spawn(move || {
+        download_video(config("slow", DownloadFormat::Mp3), "Slow".to_string(), tx, stop_rx);
+    });
+
+    // 첫 진행률이 오면 중지 요청
+    loop {
+        match rx.recv_timeout(Duration::from_secs(10)).expect("no progress from fake yt-dlp") {
+            DownloadStatus::Progress(_) => break,
+            _ => continue,
+        }
+    }
+    stop_tx.send(()).unwrap();
+
+    let last = rx.iter().last();
+    handle.join().unwrap();
+    assert!(matches!(last, Some(DownloadStatus::Stopped)), "{:?}", last);
+}
@@ -0,0 +1,221 @@
+#![cfg(unix)]
+
+mod common;
+
+use rust_yt::initializer::{download_validated, download_verified, Artifact, InitStatus};
+use rust_yt::Error;
+use std::sync::mpsc::channel;
+
+const BINARY: &[u8] = b"fake yt-dlp binary";
+const BINARY_SHA256: &str = "15cbc6ea9989fa446c06ce56e0e77f9a9ef8ae1c5167837f45d3a8e76f0fda52";
+
+fn artifact(base: &str) -> Artifact {
+    Artifact {
+        url: format!("{}/download/yt-dlp", base),
+        sums_url: Some(format!("{}/download/SHA2-256SUMS", base)),
+    }
+}
+
+#[test]
+fn installs_file_with_matching_checksum() {
+    let sums = format!("{}  yt-dlp.exe\n{}  yt-dlp\n", "0".repeat(64), BINARY_SHA256);
+    let base = common::serve(vec![
+        ("/download/yt-dlp", BINARY.to_vec()),
+        ("/download/SHA2-256SUMS", sums.into_bytes()),
+    ]);
+    let dest = common::output_dir("verify-ok").join("yt-dlp");
+    let (tx, rx) = channel();
+
+    download_verified(&artifact(&base), &dest, &tx, "yt-dlp", None).unwrap();
+
+    assert_eq!(std::fs::read(&dest).unwrap(), BINARY);
+    let verified = rx.try_iter().find_map(|s| match s {
+        InitStatus::Verified(file, hash) => Some((file, hash)),
+        _ => None,
+    });
+    assert_eq!(verified, Some(("yt-dlp".to_string(), Some(BINARY_SHA256.to_string()))));
+}
+
+#[test]
+fn refuses_file_with_different_checksum() {
+    let base = common::serve(vec![
+        ("/download/yt-dlp", b"tampered binary".to_vec()),
+        ("/download/SHA2-256SUMS", format!("{}  yt-dlp\n", BINARY_SHA256).into_bytes()),
+    ]);
+    let dest = common::output_dir("verify-mismatch").join("yt-dlp");
+    let (tx, rx) = channel();
+
+    let err = download_verified(&artifact(&base), &dest, &tx, "yt-dlp", None).unwrap_err();
+
+    assert!(matches!(err, Error::ChecksumMismatch(_)), "{:?}", err);
+    assert!(!dest.exists());
+    assert!(!rx.try_iter().any(|s| matches!(s, InitStatus::Verified(..))));
+}
+
+#[test]
+fn failed_validation_keeps_existing_binary() {
+    let sums = format!("{}  yt-dlp\n", BINARY_SHA256);
+    let base = common::serve(vec![
+        ("/download/yt-dlp", BINARY.to_vec()),
+        ("/download/SHA2-256SUMS", sums.into_bytes()),
+    ]);
+    let dest = common::output_dir("verify-validate").join("yt-dlp");
+    std::fs::write(&dest, b"working yt-dlp").unwrap();
+    let (tx, _rx) = channel();
+
+    // 검사는 옮기기 전의 `.part`에서 실행됨
+    let err = download_validated(&artifact(&base), &dest, &tx, "yt-dlp", None, |part| {
+        assert_eq!(std::fs::read(part).unwrap(), BINARY);
+        Err(Error::Io("does not run".to_string()))
+    }).unwrap_err();
+
+    assert!(matches!(err, Error::Io(_)), "{:?}", err);
+    assert_eq!(std::fs::read(&dest).unwrap(), b"working yt-dlp");
+    assert!(!dest.with_file_name("yt-dlp.part").exists());
+}
+
+#[test]
+fn refuses_file_missing_from_sums() {
+    let base = common::serve(vec![
+        ("/download/yt-dlp", BINARY.to_vec()),
+        ("/download/SHA2-256SUMS", format!("{}  yt-dlp_macos\n", BINARY_SHA256).into_bytes()),
+    ]);
+    let dest = common::output_dir("verify-unlisted").join("yt-dlp");
+    let (tx, _rx) = channel();
+
+    let err = download_verified(&artifact(&base), &dest, &tx, "yt-dlp", None).unwrap_err();
+
+    assert!(matches!(err, Error::ChecksumMismatch(_)), "{:?}", err);
+    assert!(!dest.exists());
+}
+
+#[test]
+fn reports_unverified_install_without_published_sums() {
+    let base = common::serve(vec![("/download/yt-dlp", BINARY.to_vec())]);
+    let dest = common::output_dir("verify-none").join("yt-dlp");
+    let (tx, rx) = channel();
+
+    let artifact = Artifact { sums_url: None, ..artifact(&base) };
+    download_verified(&artifact, &dest, &tx, "yt-dlp", None).unwrap();
+
+    assert!(dest.exists());
+    assert!(rx.try_iter().any(|s| matches!(s, InitStatus::Verified(_, None))));
+}
+
+#[test]
+fn interrupted_download_is_resumed_with_range() {
+    let (base, requests) = common::serve_with(vec![
+        ("/download/yt-dlp", BINARY.to_vec()),
+        ("/download/SHA2-256SUMS", format!("{}  yt-dlp\n", BINARY_SHA256).into_bytes()),
+    ], Some(("/download/yt-dlp", 8)));
+    let dest = common::output_dir("resume-range").join("yt-dlp");
+    let (tx, _rx) = channel();
+
+    download_verified(&artifact(&base), &dest, &tx, "yt-dlp", None).unwrap();
+
+    assert_eq!(std::fs::read(&dest).unwrap(), BINARY);
+    assert!(!dest.with_file_name("yt-dlp.part").exists());
+    assert!(!dest.with_file_name("yt-dlp.part.validator").exists());
+    let ranges: Vec<_> = requests.lock().unwrap().iter()
+        .filter(|(path, _)| path == "/download/yt-dlp")
+        .map(|(_, range)| range.clone())
+        .collect();
+    assert_eq!(ranges, [None, Some("bytes=8-".to_string())]);
+}
+
+#[test]
+fn leftover_part_file_is_continued_and_not_installed_early() {
+    let (base, requests) = common::serve_with(vec![("/download/yt-dlp", BINARY.to_vec())], None);
+    let dir = common::output_dir("resume-part");
+    let dest = dir.join("yt-dlp");
+    // 이전 실행이 중간에 끊겨 남긴 파일
+    std::fs::write(dir.join("yt-dlp.part"), &BINARY[..5]).unwrap();
+    std::fs::write(dir.join("yt-dlp.part.validator"), common::ETAG).unwrap();
+    assert!(!dest.exists());
+    let (tx, _rx) = channel();
+
+    let artifact = Artifact { sums_url: None, ..artifact(&base) };
+    download_verified(&artifact, &dest, &tx, "yt-dlp", None).unwrap();
+
+    assert_eq!(std::fs::read(&dest).unwrap(), BINARY);
+    assert!(!dir.join("yt-dlp.part").exists());
+    assert_eq!(requests.lock().unwrap()[0].1.as_deref(), Some("bytes=5-"));
+}
+
+#[test]
+fn stale_part_file_with_wrong_checksum_is_downloaded_again() {
+    let (base, requests) = common::serve_with(vec![
+        ("/download/yt-dlp", BINARY.to_vec()),
+        ("/download/SHA2-256SUMS", format!("{}  yt-dlp\n", BINARY_SHA256).into_bytes()),
+    ], None);
+    let dir = common::output_dir("resume-stale");
+    let dest = dir.join("yt-dlp");
+    // 서버가 바뀌지 않았다고 답했지만 실제로는 다른 내용
+    std::fs::write(dir.join("yt-dlp.part"), b"old release").unwrap();
+    std::fs::write(dir.join("yt-dlp.part.validator"), common::ETAG).unwrap();
+    let (tx, _rx) = channel();
+
+    download_verified(&artifact(&base), &dest, &tx, "yt-dlp", None).unwrap();
+
+    assert_eq!(std::fs::read(&dest).unwrap(), BINARY);
+    let ranges: Vec<_> = requests.lock().unwrap().iter()
+        .filter(|(path, _)| path == "/download/yt-dlp")
+        .map(|(_, range)| range.clone())
+        .collect();
+    assert_eq!(ranges, [Some("bytes=11-".to_string()), None]);
+}
+
+#[test]
+fn part_file_from_changed_release_is_replaced_via_if_range() {
+    let (base, requests) = common::serve_with(vec![("/download/yt-dlp", BINARY.to_vec())], None);
+    let dir = common::output_dir("resume-if-range");
+    let dest = dir.join("yt-dlp");
+    // 이전 릴리스에서 받다 만 내용 (체크섬이 없어도 이어 붙이지 않아야 함)
+    std::fs::write(dir.join("yt-dlp.part"), b"old release").unwrap();
+    std::fs::write(dir.join("yt-dlp.part.validator"), "\"old-release\"").unwrap();
+    let (tx, _rx) = channel();
+
+    let artifact = Artifact { sums_url: None, ..artifact(&base) };
+    download_verified(&artifact, &dest, &tx, "yt-dlp", None).unwrap();
+
+    assert_eq!(std::fs::read(&dest).unwrap(), BINARY);
+    assert_eq!(requests.lock().unwrap().len(), 1);
+}
+
+#[test]
+fn part_file_without_validator_is_not_resumed() {
+    let (base, requests) = common::serve_with(vec![("/download/yt-dlp", BINARY.to_vec())], None);
+    let dir = common::output_dir("resume-no-validator");
+    let dest = dir.join("yt-dlp");
+    std::fs::write(dir.join("yt-dlp.part"), b"unknown").unwrap();
+    let (tx, _rx) = channel();
+
+    let artifact = Artifact { sums_url: None, ..artifact(&base) };
+    download_verified(&artifact, &dest, &tx, "yt-dlp", None).unwrap();
+
+    assert_eq!(std::fs::read(&dest).unwrap(), BINARY);
+    assert_eq!(requests.lock().unwrap()[0].1, None);
+}
+
+#[test]
+fn retries_server_errors_but_not_missing_files() {
+    let sums = format!("{}  yt-dlp\n", BINARY_SHA256);
+    let (base, log) = common::serve_unavailable(vec![
+        ("/download/yt-dlp", BINARY.to_vec()),
+        ("/download/SHA2-256SUMS", sums.into_bytes()),
+    ], ("/download/yt-dlp", 2));
+    let dest = common::output_dir("retry-503").join("yt-dlp");
+    let (tx, _rx) = channel();
+
+    download_verified(&artifact(&base), &dest, &tx, "yt-dlp", None).unwrap();
+    assert_eq!(std::fs::read(&dest).unwrap(), BINARY);
+    let attempts = log.lock().unwrap().iter().filter(|(path, _)| path == "/download/yt-dlp").count();
+    assert_eq!(attempts, 3);
+
+    // 404는 다시 시도하지 않음
+    let (base, log) = common::serve_with(vec![], None);
+    let artifact = Artifact { sums_url: None, ..artifact(&base) };
+    let dest = common::output_dir("retry-404").join("yt-dlp");
+    assert!(download_verified(&artifact, &dest, &tx, "yt-dlp", None).is_err());
+    assert_eq!(log.lock().unwrap().len(), 1);
+}
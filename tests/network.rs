@@ -0,0 +1,52 @@
+use rust_yt::network::test_connection;
+use rust_yt::Error;
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::sync::mpsc::{channel, Receiver};
+use std::thread;
+
+/// 요청 한 건에 `status_line`으로 응답하는 가짜 HTTP 프록시 (받은 요청 줄 반환)
+fn fake_proxy(status_line: &'static str) -> (String, Receiver<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = format!("http://{}", listener.local_addr().unwrap());
+    let (tx, rx) = channel();
+
+    thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line).unwrap();
+        // 헤더 끝까지 읽기
+        let mut line = String::new();
+        while reader.read_line(&mut line).unwrap() > 2 {
+            line.clear();
+        }
+        let mut stream = stream;
+        write!(stream, "{}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n", status_line).unwrap();
+        tx.send(request_line.trim().to_string()).unwrap();
+    });
+
+    (addr, rx)
+}
+
+#[test]
+fn connection_test_goes_through_proxy() {
+    let (proxy, requests) = fake_proxy("HTTP/1.1 204 No Content");
+    test_connection(Some(&proxy), "http://youtube.test/generate_204").unwrap();
+
+    // 프록시는 전체 URL이 담긴 요청을 받음
+    assert_eq!(requests.recv().unwrap(), "GET http://youtube.test/generate_204 HTTP/1.1");
+}
+
+#[test]
+fn connection_test_reports_http_errors() {
+    let (proxy, _requests) = fake_proxy("HTTP/1.1 407 Proxy Authentication Required");
+    let err = test_connection(Some(&proxy), "http://youtube.test/generate_204").unwrap_err();
+    assert!(matches!(err, Error::Network(_)), "{:?}", err);
+}
+
+#[test]
+fn invalid_proxy_is_rejected_before_connecting() {
+    let err = test_connection(Some("127.0.0.1:8080"), "http://youtube.test/").unwrap_err();
+    assert_eq!(err, Error::InvalidProxy("127.0.0.1:8080".to_string()));
+}
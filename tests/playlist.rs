@@ -0,0 +1,162 @@
+#![cfg(unix)]
+
+mod common;
+
+use rust_yt::auth::AuthOptions;
+use rust_yt::downloader::DownloadFormat;
+use rust_yt::playlist::{fetch_formats, fetch_playlist_info};
+use rust_yt::Error;
+
+#[test]
+fn fetches_single_video() {
+    common::setup();
+    let info = fetch_playlist_info(&common::url("single"), &AuthOptions::default(), None).unwrap();
+
+    assert!(!info.is_playlist);
+    assert_eq!(info.title, "Single Video");
+    assert_eq!(info.entries.len(), 1);
+
+    let entry = &info.entries[0];
+    assert_eq!(entry.id, "vid001");
+    assert_eq!(entry.url, "https://www.youtube.com/watch?v=vid001");
+    assert_eq!(entry.thumbnail.as_deref(), Some("https://i.ytimg.com/vi/vid001/hq.jpg"));
+    assert_eq!(entry.format_duration(), "2:05");
+    assert_eq!(entry.uploader.as_deref(), Some("Test Channel"));
+
+    let subtitles: Vec<(&str, Option<&str>, bool)> = entry.subtitles.iter()
+        .map(|s| (s.language.as_str(), s.name.as_deref(), s.auto))
+        .collect();
+    assert_eq!(subtitles, [
+        ("en", Some("English"), false),
+        ("ko", Some("Korean"), false),
+        ("en", Some("English (auto)"), true),
+    ]);
+
+    let chapters: Vec<(&str, String, String)> = entry.chapters.iter()
+        .map(|c| (c.title.as_str(), c.format_start(), c.format_length()))
+        .collect();
+    assert_eq!(chapters, [
+        ("Intro", "0:00".to_string(), "0:12".to_string()),
+        ("Main", "0:12".to_string(), "1:52".to_string()),
+    ]);
+    assert!(entry.chapters.iter().all(|c| c.selected));
+    assert!(entry.selected);
+
+    let args = common::recorded_args("single");
+    assert!(args.contains(&"--flat-playlist".to_string()));
+    assert!(args.contains(&"-J".to_string()));
+}
+
+#[test]
+fn single_video_without_id_keeps_input_url() {
+    common::setup();
+    let url = common::url("single-no-id");
+    let info = fetch_playlist_info(&url, &AuthOptions::default(), None).unwrap();
+
+    let entry = &info.entries[0];
+    assert_eq!(entry.id, "");
+    assert_eq!(entry.url, url);
+    assert_eq!(entry.format_duration(), "1:01");
+    assert!(entry.chapters.is_empty());
+}
+
+#[test]
+fn fetches_playlist_and_skips_entries_without_id() {
+    common::setup();
+    let info = fetch_playlist_info(&common::url("playlist"), &AuthOptions::default(), None).unwrap();
+
+    assert!(info.is_playlist);
+    assert_eq!(info.title, "Test Playlist");
+
+    let ids: Vec<&str> = info.entries.iter().map(|e| e.id.as_str()).collect();
+    assert_eq!(ids, ["pl001", "pl003"]);
+    // id 없는 항목을 건너뛰어도 플레이리스트 번호는 유지
+    let indexes: Vec<Option<usize>> = info.entries.iter().map(|e| e.playlist_index).collect();
+    assert_eq!(indexes, [Some(1), Some(3)]);
+    assert_eq!(info.last_index(), 3);
+    assert!(info.entries.iter().all(|e| e.selected));
+    assert_eq!(info.entries[0].uploader.as_deref(), Some("Entry Channel"));
+
+    // url/title이 없는 항목은 id로 URL을 만들고 기본 제목 사용
+    let last = &info.entries[1];
+    assert_eq!(last.url, "https://www.youtube.com/watch?v=pl003");
+    assert_eq!(last.title, "Untitled");
+    assert_eq!(last.format_duration(), "62:05");
+    assert_eq!(last.uploader.as_deref(), Some("Playlist Owner"));
+}
+
+#[test]
+fn deselects_entries_downloaded_in_target_format() {
+    common::setup();
+    let mut info = fetch_playlist_info(&common::url("playlist"), &AuthOptions::default(), None).unwrap();
+    info.entries[0].downloaded_formats = vec![DownloadFormat::Mp3];
+
+    // 다른 형식으로 받은 영상은 그대로 선택
+    info.select_for_format(&DownloadFormat::Mp4);
+    assert!(info.entries.iter().all(|e| e.selected));
+
+    info.select_for_format(&DownloadFormat::Mp3);
+    let selected: Vec<bool> = info.entries.iter().map(|e| e.selected).collect();
+    assert_eq!(selected, [false, true]);
+}
+
+#[test]
+fn classifies_unavailable_video() {
+    common::setup();
+    let err = fetch_playlist_info(&common::url("unavailable"), &AuthOptions::default(), None).unwrap_err();
+    assert!(matches!(err, Error::VideoUnavailable(_)), "{:?}", err);
+}
+
+#[test]
+fn sign_in_errors_ask_for_cookies_and_pass_proxy() {
+    common::setup();
+    let auth = AuthOptions {
+        cookies_browser: Some("firefox".to_string()),
+        browser_profile: Some("work".to_string()),
+        ..AuthOptions::default()
+    };
+    let err = fetch_playlist_info(&common::url("signin"), &auth, Some("socks5://127.0.0.1:1080")).unwrap_err();
+    assert!(matches!(err, Error::AuthRequired(_)), "{:?}", err);
+
+    let args = common::recorded_args("signin");
+    let pos = args.iter().position(|a| a == "--cookies-from-browser").unwrap();
+    assert_eq!(args[pos + 1], "firefox:work");
+    let pos = args.iter().position(|a| a == "--proxy").unwrap();
+    assert_eq!(args[pos + 1], "socks5://127.0.0.1:1080");
+}
+
+#[test]
+fn reports_invalid_json() {
+    common::setup();
+    let err = fetch_playlist_info(&common::url("garbage"), &AuthOptions::default(), None).unwrap_err();
+    assert!(matches!(err, Error::Parse(_)), "{:?}", err);
+}
+
+#[test]
+fn fetches_formats_without_storyboards() {
+    common::setup();
+    let formats = fetch_formats(&common::url("formats"), &AuthOptions::default(), None).unwrap();
+
+    let ids: Vec<&str> = formats.iter().map(|f| f.format_id.as_str()).collect();
+    assert_eq!(ids, ["140", "18", "299"]);
+
+    let audio = &formats[0];
+    assert!(audio.has_audio() && !audio.has_video());
+    assert_eq!(audio.resolution_string(), "audio only");
+    assert_eq!(audio.language.as_deref(), Some("en"));
+
+    let muxed = &formats[1];
+    assert!(muxed.has_audio() && muxed.has_video());
+    assert_eq!(muxed.codec_string(), "avc1.42001E + mp4a.40.2");
+    assert_eq!(muxed.size_string(), "~7.0 MiB");
+
+    let video = &formats[2];
+    assert!(!video.has_audio());
+    assert_eq!(video.resolution_string(), "1920x1080");
+    assert_eq!(video.fps, Some(60.0));
+    assert_eq!(video.bitrate_string(), "4500 kbps");
+
+    let args = common::recorded_args("formats");
+    assert!(!args.contains(&"--flat-playlist".to_string()));
+    assert!(args.contains(&"--no-playlist".to_string()));
+}
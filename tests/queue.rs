@@ -0,0 +1,41 @@
+#![cfg(unix)]
+
+mod common;
+
+use rust_yt::downloader::{DownloadConfig, DownloadFormat};
+use rust_yt::queue::{run_queue, ItemState, QueueEvent, QueueItem};
+use std::sync::mpsc::channel;
+use std::time::Duration;
+
+#[test]
+fn queue_waits_between_items_instead_of_yt_dlp() {
+    common::setup();
+    let scenarios = ["ok-audio-queue-1", "ok-audio-queue-2", "ok-audio-queue-3"];
+    let items = scenarios.iter()
+        .map(|scenario| {
+            let mut config = DownloadConfig::new(common::url(scenario), DownloadFormat::Mp3, common::output_dir(scenario));
+            config.sleep_interval = Some((1, 1));
+            QueueItem { id: scenario.to_string(), title: scenario.to_string(), config }
+        })
+        .collect();
+
+    let (tx, rx) = channel();
+    let _handle = run_queue(items, 2, tx);
+    let mut waited = Vec::new();
+    loop {
+        match rx.recv_timeout(Duration::from_secs(10)).expect("queue did not finish") {
+            QueueEvent::Updated(idx, ItemState::Waiting(secs), _) => waited.push((idx, secs)),
+            QueueEvent::Updated(..) => continue,
+            QueueEvent::Finished(progress) => {
+                assert_eq!(progress.completed, 3);
+                break;
+            }
+        }
+    }
+
+    // 동시에 시작하는 처음 두 항목은 바로 받고, 이후 항목은 스케줄러가 대기 상태로 기다린 뒤 시작
+    assert_eq!(waited, [(2, 1)]);
+    for scenario in scenarios {
+        assert!(!common::recorded_args(scenario).contains(&"--sleep-interval".to_string()));
+    }
+}
@@ -0,0 +1,47 @@
+#![cfg(unix)]
+
+//! 설정에서 지정한 yt-dlp/ffmpeg만 쓰는 초기화
+//!
+//! 다른 테스트는 `RUST_YT_YTDLP`로 가짜 yt-dlp를 지정하는데, 환경 변수가 설정보다
+//! 우선하므로 이 파일은 `common`을 쓰지 않고 따로 실행한다.
+
+use rust_yt::binaries::{set_configured_paths, Tool};
+use rust_yt::initializer::{init_dependencies, InitStatus};
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::channel;
+
+fn write_script(dir: &Path, name: &str, body: &str) -> PathBuf {
+    let path = dir.join(name);
+    fs::write(&path, format!("#!/bin/sh\n{}\n", body)).unwrap();
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+    path
+}
+
+fn run_init(auto_download: bool) -> Vec<InitStatus> {
+    let (tx, rx) = channel();
+    init_dependencies(tx, None, auto_download);
+    rx.try_iter().collect()
+}
+
+#[test]
+fn configured_tools_are_checked_without_downloading() {
+    let dir = std::env::temp_dir().join(format!("rust-yt-system-tools-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    // `-U` 등 버전 확인 외의 호출은 실패시켜 업데이트를 시도하지 않는지 확인
+    let ytdlp = write_script(&dir, "yt-dlp", r#"[ "$1" = "--version" ] || exit 1; echo 2099.01.01"#);
+    let ffmpeg = write_script(&dir, "ffmpeg", "echo 'ffmpeg version 7.0'");
+
+    // 지정 경로가 틀리면 자동 설치가 켜져 있어도 내려받지 않고 알림
+    set_configured_paths(Some(dir.join("missing-yt-dlp")), Some(ffmpeg.clone()));
+    let statuses = run_init(true);
+    assert!(matches!(statuses.as_slice(), [InitStatus::Missing(tools)] if tools == &[Tool::YtDlp]), "{:?}", statuses);
+
+    // 자동 설치를 끄면 버전만 확인
+    set_configured_paths(Some(ytdlp), Some(ffmpeg));
+    let statuses = run_init(false);
+    assert!(!statuses.iter().any(|s| matches!(s, InitStatus::Downloading(..))));
+    assert!(statuses.iter().any(|s| matches!(s, InitStatus::Starting(msg) if msg.contains("2099.01.01"))), "{:?}", statuses);
+    assert!(matches!(statuses.last(), Some(InitStatus::Completed)));
+}